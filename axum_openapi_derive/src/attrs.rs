@@ -0,0 +1,467 @@
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+use crate::case::RenameRule;
+
+/// How the variants of an enum are represented, see <https://serde.rs/enum-representations.html>
+pub enum TagType {
+    External,
+    Internal { tag: String },
+    Adjacent { tag: String, content: String },
+    None,
+}
+
+/// A name which can be different for serialization and deserialization
+pub struct Name {
+    pub serialize: String,
+    pub deserialize: String,
+    /// The `#[serde(alias = "...")]` names, which are accepted in addition to `deserialize`
+    pub aliases: Vec<String>,
+}
+
+impl Name {
+    fn plain(name: String) -> Self {
+        Name {
+            serialize: name.clone(),
+            deserialize: name,
+            aliases: Vec::new(),
+        }
+    }
+
+    fn with_rule(name: &str, rename_all: RenameAll, apply: fn(RenameRule, &str) -> String) -> Self {
+        Name {
+            serialize: apply(rename_all.serialize, name),
+            deserialize: apply(rename_all.deserialize, name),
+            aliases: Vec::new(),
+        }
+    }
+
+    fn rename(&mut self, (serialize, deserialize): (Option<String>, Option<String>)) {
+        if let Some(serialize) = serialize {
+            self.serialize = serialize;
+        }
+        if let Some(deserialize) = deserialize {
+            self.deserialize = deserialize;
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct RenameAll {
+    pub serialize: RenameRule,
+    pub deserialize: RenameRule,
+}
+
+impl RenameAll {
+    pub const NONE: RenameAll = RenameAll {
+        serialize: RenameRule::None,
+        deserialize: RenameRule::None,
+    };
+
+    fn parse(meta: &Meta) -> syn::Result<Self> {
+        let rule = |rule: Option<String>| match rule {
+            None => Ok(RenameRule::None),
+            Some(rule) => RenameRule::parse(&rule).ok_or_else(|| {
+                syn::Error::new_spanned(meta, format!("unknown rename rule `{}`", rule))
+            }),
+        };
+        let (serialize, deserialize) = ser_de_strs(meta)?;
+        Ok(RenameAll {
+            serialize: rule(serialize)?,
+            deserialize: rule(deserialize)?,
+        })
+    }
+}
+
+/// The doc comments of an item.
+///
+/// If there is more than one paragraph, the first one is used as the title.
+#[derive(Default)]
+pub struct Docs {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Docs {
+    /// Doc attributes which are not a string literal, like `#[doc = include_str!("...")]`, are skipped,
+    /// because their value is only known after macro expansion.
+    fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut lines = Vec::new();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("doc")) {
+            if let Ok(Meta::NameValue(syn::MetaNameValue {
+                lit: Lit::Str(line),
+                ..
+            })) = attr.parse_meta()
+            {
+                let line = line.value();
+                let line = line.strip_prefix(' ').unwrap_or(&line);
+                lines.push(line.trim_end().to_string());
+            }
+        }
+        let docs = lines.join("\n");
+        let docs = docs.trim();
+        if docs.is_empty() {
+            return Docs::default();
+        }
+
+        match docs.split_once("\n\n") {
+            Some((title, description)) => Docs {
+                title: Some(title.trim().to_string()),
+                description: Some(description.trim().to_string()),
+            },
+            None => Docs {
+                title: None,
+                description: Some(docs.to_string()),
+            },
+        }
+    }
+}
+
+impl Docs {
+    /// The title and description as a single text
+    pub fn text(&self) -> Option<String> {
+        match (&self.title, &self.description) {
+            (Some(title), Some(description)) => Some(format!("{}\n\n{}", title, description)),
+            (title, description) => title.clone().or_else(|| description.clone()),
+        }
+    }
+}
+
+/// The `#[serde(...)]` and `#[openapi(...)]` attributes and docs on a struct or enum
+pub struct ContainerAttrs {
+    pub docs: Docs,
+    pub name: Name,
+    /// `#[openapi(ref_name = "...")]`, a template for the component name with `{T}` placeholders for the generic parameters
+    pub ref_name: Option<syn::LitStr>,
+    pub rename_all: RenameAll,
+    pub tag: TagType,
+    pub transparent: bool,
+    /// `#[serde(default)]` on the container, which makes every field optional
+    pub default: bool,
+}
+
+impl ContainerAttrs {
+    pub fn from_input(input: &syn::DeriveInput) -> syn::Result<Self> {
+        let mut name = Name::plain(unraw(&input.ident));
+        let mut rename_all = RenameAll::NONE;
+        let mut tag = None;
+        let mut content = None;
+        let mut untagged = None;
+        let mut transparent = false;
+        let mut default = false;
+        let mut ref_name = None;
+
+        for meta in metas(&input.attrs, "openapi")? {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("ref_name") => match &nv.lit {
+                    Lit::Str(lit) => ref_name = Some(lit.clone()),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected string literal")),
+                },
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other.path(),
+                        "unknown attribute, expected `ref_name = \"...\"`",
+                    ))
+                }
+            }
+        }
+
+        for meta in metas(&input.attrs, "serde")? {
+            match &meta {
+                meta if meta.path().is_ident("rename") => name.rename(ser_de_strs(meta)?),
+                meta if meta.path().is_ident("rename_all") => rename_all = RenameAll::parse(meta)?,
+                Meta::NameValue(nv) if nv.path.is_ident("tag") => tag = Some(lit_str(&nv.lit)?),
+                Meta::NameValue(nv) if nv.path.is_ident("content") => {
+                    content = Some(lit_str(&nv.lit)?)
+                }
+                Meta::Path(path) if path.is_ident("untagged") => untagged = Some(path.clone()),
+                Meta::Path(path) if path.is_ident("transparent") => transparent = true,
+                meta if meta.path().is_ident("default") => default = true,
+                _ => {}
+            }
+        }
+
+        let tag = match (untagged, tag, content) {
+            (None, None, None) => TagType::External,
+            (None, Some(tag), None) => TagType::Internal { tag },
+            (None, Some(tag), Some(content)) => TagType::Adjacent { tag, content },
+            (Some(_), None, None) => TagType::None,
+            (Some(untagged), _, _) => {
+                return Err(syn::Error::new_spanned(
+                    untagged,
+                    "untagged enums cannot have a tag or content",
+                ))
+            }
+            (None, None, Some(_)) => {
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    "#[serde(content = \"...\")] requires a tag",
+                ))
+            }
+        };
+
+        Ok(ContainerAttrs {
+            docs: Docs::from_attrs(&input.attrs),
+            name,
+            ref_name,
+            rename_all,
+            tag,
+            transparent,
+            default,
+        })
+    }
+}
+
+/// The `#[serde(...)]` attributes on an enum variant
+pub struct VariantAttrs {
+    pub name: Name,
+    pub rename_all: RenameAll,
+    pub skip_serializing: bool,
+}
+
+impl VariantAttrs {
+    pub fn from_variant(variant: &syn::Variant, container: &ContainerAttrs) -> syn::Result<Self> {
+        let mut name = Name::with_rule(
+            &unraw(&variant.ident),
+            container.rename_all,
+            RenameRule::apply_to_variant,
+        );
+        let mut rename_all = RenameAll::NONE;
+        let mut skip_serializing = false;
+
+        for meta in metas(&variant.attrs, "serde")? {
+            match &meta {
+                meta if meta.path().is_ident("rename") => name.rename(ser_de_strs(meta)?),
+                meta if meta.path().is_ident("rename_all") => rename_all = RenameAll::parse(meta)?,
+                Meta::Path(path) if path.is_ident("skip") || path.is_ident("skip_serializing") => {
+                    skip_serializing = true
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("alias") => {
+                    name.aliases.push(lit_str(&nv.lit)?)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(VariantAttrs {
+            name,
+            rename_all,
+            skip_serializing,
+        })
+    }
+}
+
+/// The `#[openapi(status = ...)]` attribute and docs on a variant of an enum deriving `DescribeResponses`
+pub struct ResponseAttrs {
+    pub docs: Docs,
+    pub status: u16,
+}
+
+impl ResponseAttrs {
+    pub fn from_variant(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut status = None;
+        for meta in metas(&variant.attrs, "openapi")? {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("status") => match &nv.lit {
+                    Lit::Int(lit) => match lit.base10_parse::<u16>()? {
+                        code @ 100..=599 => status = Some(code),
+                        _ => return Err(syn::Error::new_spanned(lit, "invalid status code")),
+                    },
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a status code")),
+                },
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other.path(),
+                        "unknown attribute, expected `status = ...`",
+                    ))
+                }
+            }
+        }
+
+        let status = status.ok_or_else(|| {
+            syn::Error::new_spanned(&variant.ident, "missing #[openapi(status = ...)]")
+        })?;
+        Ok(ResponseAttrs {
+            docs: Docs::from_attrs(&variant.attrs),
+            status,
+        })
+    }
+}
+
+/// The `#[openapi(content_type = "...")]` attribute and docs on a field of a struct deriving `DescribeMultipart`
+pub struct PartAttrs {
+    pub docs: Docs,
+    pub content_type: Option<String>,
+}
+
+impl PartAttrs {
+    pub fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut content_type = None;
+        for meta in metas(&field.attrs, "openapi")? {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("content_type") => {
+                    content_type = Some(lit_str(&nv.lit)?)
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other.path(),
+                        "unknown attribute, expected `content_type = \"...\"`",
+                    ))
+                }
+            }
+        }
+
+        Ok(PartAttrs {
+            docs: Docs::from_attrs(&field.attrs),
+            content_type,
+        })
+    }
+}
+
+/// The `#[serde(...)]` attributes and docs on a struct or variant field
+pub struct FieldAttrs {
+    pub docs: Docs,
+    pub name: Name,
+    pub skip_serializing: bool,
+    pub skip_deserializing: bool,
+    pub skip_serializing_if: bool,
+    pub flatten: bool,
+    /// `#[serde(default)]` or `#[serde(default = "...")]`
+    pub default: bool,
+}
+
+impl FieldAttrs {
+    /// `rename_all` is the rule of the struct or variant containing the field
+    pub fn from_field(
+        field: &syn::Field,
+        index: usize,
+        rename_all: RenameAll,
+    ) -> syn::Result<Self> {
+        let mut name = match &field.ident {
+            Some(ident) => Name::with_rule(&unraw(ident), rename_all, RenameRule::apply_to_field),
+            None => Name::plain(index.to_string()),
+        };
+        let mut skip_serializing = false;
+        let mut skip_deserializing = false;
+        let mut skip_serializing_if = false;
+        let mut flatten = false;
+        let mut default = false;
+
+        for meta in metas(&field.attrs, "serde")? {
+            match &meta {
+                meta if meta.path().is_ident("rename") => name.rename(ser_de_strs(meta)?),
+                Meta::Path(path) if path.is_ident("skip") => {
+                    skip_serializing = true;
+                    skip_deserializing = true;
+                }
+                Meta::Path(path) if path.is_ident("skip_serializing") => skip_serializing = true,
+                Meta::Path(path) if path.is_ident("skip_deserializing") => {
+                    skip_deserializing = true
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("skip_serializing_if") => {
+                    lit_str(&nv.lit)?;
+                    skip_serializing_if = true;
+                }
+                Meta::Path(path) if path.is_ident("flatten") => flatten = true,
+                Meta::Path(path) if path.is_ident("default") => default = true,
+                Meta::NameValue(nv) if nv.path.is_ident("default") => {
+                    lit_str(&nv.lit)?;
+                    default = true;
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("alias") => {
+                    name.aliases.push(lit_str(&nv.lit)?)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(FieldAttrs {
+            docs: Docs::from_attrs(&field.attrs),
+            name,
+            skip_serializing,
+            skip_deserializing,
+            skip_serializing_if,
+            flatten,
+            default,
+        })
+    }
+
+    /// Whether the field never appears in either direction
+    pub fn skipped(&self) -> bool {
+        self.skip_serializing && self.skip_deserializing
+    }
+}
+
+/// Flattens all `#[name(a, b = "c")]` attributes into their inner items
+fn metas(attrs: &[Attribute], name: &str) -> syn::Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    format!("expected #[{}(...)]", name),
+                ))
+            }
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(meta) => metas.push(meta),
+                NestedMeta::Lit(lit) => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!("unexpected literal in #[{}]", name),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(metas)
+}
+
+/// Parses both `name = "value"` and `name(serialize = "a", deserialize = "b")`
+fn ser_de_strs(meta: &Meta) -> syn::Result<(Option<String>, Option<String>)> {
+    match meta {
+        Meta::NameValue(nv) => {
+            let value = lit_str(&nv.lit)?;
+            Ok((Some(value.clone()), Some(value)))
+        }
+        Meta::List(list) => {
+            let mut serialize = None;
+            let mut deserialize = None;
+            for nested in &list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("serialize") => {
+                        serialize = Some(lit_str(&nv.lit)?)
+                    }
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("deserialize") => {
+                        deserialize = Some(lit_str(&nv.lit)?)
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `serialize = \"...\"` or `deserialize = \"...\"`",
+                        ))
+                    }
+                }
+            }
+            Ok((serialize, deserialize))
+        }
+        Meta::Path(path) => Err(syn::Error::new_spanned(path, "expected a value")),
+    }
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(lit) => Ok(lit.value()),
+        _ => Err(syn::Error::new_spanned(lit, "expected string literal")),
+    }
+}
+
+fn unraw(ident: &syn::Ident) -> String {
+    let ident = ident.to_string();
+    match ident.strip_prefix("r#") {
+        Some(ident) => ident.to_string(),
+        None => ident,
+    }
+}
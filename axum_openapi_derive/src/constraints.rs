@@ -0,0 +1,365 @@
+//! Validation constraints on fields, written as `#[openapi(min_length = 1, pattern = "^[a-z]+$")]`
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, Lit, Token,
+};
+
+/// A single `key` or `key = value` inside of `#[openapi(...)]`
+struct Constraint {
+    key: Ident,
+    negative: bool,
+    value: Option<Lit>,
+}
+
+impl Parse for Constraint {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        if !input.peek(Token![=]) {
+            return Ok(Constraint {
+                key,
+                negative: false,
+                value: None,
+            });
+        }
+        input.parse::<Token![=]>()?;
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        Ok(Constraint {
+            key,
+            negative,
+            value: Some(input.parse()?),
+        })
+    }
+}
+
+impl Constraint {
+    fn value(&self) -> syn::Result<&Lit> {
+        match &self.value {
+            Some(value) if !self.negative || matches!(value, Lit::Int(_) | Lit::Float(_)) => {
+                Ok(value)
+            }
+            Some(value) => Err(syn::Error::new_spanned(value, "unexpected `-`")),
+            None => Err(syn::Error::new_spanned(
+                &self.key,
+                format!("expected `{} = ...`", self.key),
+            )),
+        }
+    }
+
+    fn usize(&self) -> syn::Result<usize> {
+        match self.value()? {
+            Lit::Int(int) if !self.negative => int.base10_parse(),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "expected a non-negative integer",
+            )),
+        }
+    }
+
+    fn str(&self) -> syn::Result<String> {
+        match self.value()? {
+            Lit::Str(str) => Ok(str.value()),
+            other => Err(syn::Error::new_spanned(other, "expected string literal")),
+        }
+    }
+
+    fn bool(&self) -> syn::Result<bool> {
+        match &self.value {
+            None => Ok(true),
+            Some(Lit::Bool(bool)) => Ok(bool.value),
+            Some(other) => Err(syn::Error::new_spanned(other, "expected `true` or `false`")),
+        }
+    }
+
+    /// An `i64` or `f64` literal, which is converted to the bound type of the field by `NumberConstraints::bound`, and its span
+    fn bound(&self) -> syn::Result<(TokenStream, Span)> {
+        let (lit, span) = match self.value()? {
+            Lit::Int(int) => {
+                let lit = syn::LitInt::new(&format!("{}i64", int.base10_digits()), int.span());
+                (quote!(#lit), int.span())
+            }
+            Lit::Float(float) => {
+                let lit =
+                    syn::LitFloat::new(&format!("{}f64", float.base10_digits()), float.span());
+                (quote!(#lit), float.span())
+            }
+            other => return Err(syn::Error::new_spanned(other, "expected a number")),
+        };
+        let minus = self.negative.then(|| quote!(-));
+        Ok((quote_spanned! {span=> #minus #lit }, span))
+    }
+}
+
+/// Constraints grouped by the kind of schema they apply to, each with the span of its first key for error messages
+#[derive(Default)]
+pub struct Constraints {
+    string: Option<(Span, Vec<TokenStream>)>,
+    number: Option<(Span, Vec<TokenStream>)>,
+    array: Option<(Span, Vec<TokenStream>)>,
+    /// `format = "..."`, which applies to strings, integers and numbers alike
+    format: Option<String>,
+}
+
+impl Constraints {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut constraints = Constraints::default();
+        let mut seen: Vec<String> = Vec::new();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("openapi")) {
+            let parsed =
+                attr.parse_args_with(Punctuated::<Constraint, Token![,]>::parse_terminated)?;
+            for constraint in parsed {
+                let key = constraint.key.to_string();
+                // `exclusive_minimum = x` sets the minimum as well
+                let bound_key = key.trim_start_matches("exclusive_").to_string();
+                if seen.contains(&bound_key) {
+                    return Err(syn::Error::new_spanned(
+                        &constraint.key,
+                        format!("duplicate constraint `{}`", bound_key),
+                    ));
+                }
+                seen.push(bound_key);
+
+                if key == "format" {
+                    constraints.format = Some(constraint.str()?);
+                    continue;
+                }
+
+                let span = constraint.key.span();
+                let (group, patch) = match key.as_str() {
+                    "min_length" | "max_length" => {
+                        let value = constraint.usize()?;
+                        let key = &constraint.key;
+                        (
+                            &mut constraints.string,
+                            quote! { string.#key = Some(#value); },
+                        )
+                    }
+                    "pattern" => {
+                        let pattern = constraint.str()?;
+                        (
+                            &mut constraints.string,
+                            quote! { string.pattern = Some(#pattern.to_string()); },
+                        )
+                    }
+                    "minimum" | "maximum" | "multiple_of" => {
+                        let (bound, span) = constraint.bound()?;
+                        let key = &constraint.key;
+                        (
+                            &mut constraints.number,
+                            quote_spanned! {span=> number.#key = number.bound(#bound); },
+                        )
+                    }
+                    "exclusive_minimum" | "exclusive_maximum" => {
+                        let (bound, span) = constraint.bound()?;
+                        let key = &constraint.key;
+                        let bound_key = Ident::new(&key.to_string()[10..], key.span());
+                        (
+                            &mut constraints.number,
+                            quote_spanned! {span=> number.#bound_key = number.bound(#bound); number.#key = true; },
+                        )
+                    }
+                    "min_items" | "max_items" => {
+                        let value = constraint.usize()?;
+                        let key = &constraint.key;
+                        (
+                            &mut constraints.array,
+                            quote! { array.#key = Some(#value); },
+                        )
+                    }
+                    "unique_items" => {
+                        let value = constraint.bool()?;
+                        (
+                            &mut constraints.array,
+                            quote! { array.unique_items = #value; },
+                        )
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &constraint.key,
+                            format!("unknown constraint `{}`", key),
+                        ))
+                    }
+                };
+                group
+                    .get_or_insert_with(|| (span, Vec::new()))
+                    .1
+                    .push(patch);
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.string.is_none()
+            && self.number.is_none()
+            && self.array.is_none()
+            && self.format.is_none()
+    }
+
+    /// Applies the constraints to `schema`, a `ReferenceOr<Schema>` of a field of type `ty`.
+    ///
+    /// The type is checked to implement `StringSchema`, `NumberSchema` or `ArraySchema`,
+    /// so that e.g. a `pattern` on an integer is reported at the constraint.
+    /// A `format` fits strings, integers and numbers, so its schema is only checked when it is built.
+    pub fn apply(
+        &self,
+        ty: &syn::Type,
+        mut schema: TokenStream,
+        macro_exports: &TokenStream,
+    ) -> TokenStream {
+        // the trait bound errors are reported at the span of the type, which should be the constraint
+        let respan = |span: Span| respan(quote!(#ty), span);
+        if let Some((span, patches)) = &self.string {
+            let ty = respan(*span);
+            let constrain = quote_spanned!(*span=> #macro_exports::constrain_string::<#ty>);
+            schema = quote! { #constrain(#schema, |string| { #(#patches)* }) };
+        }
+        if let Some((span, patches)) = &self.number {
+            let ty = respan(*span);
+            let constrain = quote_spanned!(*span=> #macro_exports::constrain_number::<#ty>);
+            schema = quote! { #constrain(#schema, |number| { #(#patches)* }) };
+        }
+        if let Some((span, patches)) = &self.array {
+            let ty = respan(*span);
+            let constrain = quote_spanned!(*span=> #macro_exports::constrain_array::<#ty>);
+            schema = quote! { #constrain(#schema, |array| { #(#patches)* }) };
+        }
+        if let Some(format) = &self.format {
+            schema = quote! { #macro_exports::constrain_format(#schema, #format) };
+        }
+        schema
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Constraints;
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+    use syn::{parse_quote, Attribute};
+
+    /// The schema expression `schema` with the constraints of `attr` applied for a field of type `ty`
+    fn constrained(attr: Attribute, ty: syn::Type) -> String {
+        let constraints = Constraints::from_attrs(&[attr]).unwrap();
+        constraints
+            .apply(&ty, quote!(schema), &quote!(m))
+            .to_string()
+    }
+
+    fn error(attr: Attribute) -> String {
+        match Constraints::from_attrs(&[attr]) {
+            Ok(_) => panic!("expected an error"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn string_constraints() {
+        let schema = constrained(
+            parse_quote!(#[openapi(min_length = 1, max_length = 8, pattern = "^[a-z]+$")]),
+            parse_quote!(String),
+        );
+        let expected = quote! {
+            m::constrain_string::<String>(schema, |string| {
+                string.min_length = Some(1usize);
+                string.max_length = Some(8usize);
+                string.pattern = Some("^[a-z]+$".to_string());
+            })
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn number_constraints() {
+        let schema = constrained(
+            parse_quote!(#[openapi(exclusive_minimum = -1, maximum = 2.5, multiple_of = 2)]),
+            parse_quote!(f64),
+        );
+        let expected = quote! {
+            m::constrain_number::<f64>(schema, |number| {
+                number.minimum = number.bound(-1i64);
+                number.exclusive_minimum = true;
+                number.maximum = number.bound(2.5f64);
+                number.multiple_of = number.bound(2i64);
+            })
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn array_constraints() {
+        let schema = constrained(
+            parse_quote!(#[openapi(min_items = 1, max_items = 3, unique_items)]),
+            parse_quote!(Vec<u8>),
+        );
+        let expected = quote! {
+            m::constrain_array::<Vec<u8> >(schema, |array| {
+                array.min_items = Some(1usize);
+                array.max_items = Some(3usize);
+                array.unique_items = true;
+            })
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn formats_apply_to_any_type() {
+        let schema = constrained(
+            parse_quote!(#[openapi(format = "int32", minimum = 0)]),
+            parse_quote!(u32),
+        );
+        let expected = quote! {
+            m::constrain_format(
+                m::constrain_number::<u32>(schema, |number| {
+                    number.minimum = number.bound(0i64);
+                }),
+                "int32"
+            )
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn invalid_constraints_are_errors() {
+        assert_eq!(
+            error(parse_quote!(#[openapi(min_length = "1")])),
+            "expected a non-negative integer"
+        );
+        assert_eq!(
+            error(parse_quote!(#[openapi(pattern = 1)])),
+            "expected string literal"
+        );
+        assert_eq!(
+            error(parse_quote!(#[openapi(minimum = "1")])),
+            "expected a number"
+        );
+        assert_eq!(
+            error(parse_quote!(#[openapi(max_length = 1, max_length = 2)])),
+            "duplicate constraint `max_length`"
+        );
+        assert_eq!(
+            error(parse_quote!(#[openapi(length = 1)])),
+            "unknown constraint `length`"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+use crate::add_trait_bounds;
+use crate::attrs::PartAttrs;
+
+pub fn derive_multipart(item: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(item)?;
+
+    let axum_openapi = quote!(axum_openapi);
+    let macro_exports = quote!(#axum_openapi::__macro);
+
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "DescribeMultipart can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    add_trait_bounds(&mut generics, quote!(#axum_openapi::DescribeSchema));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    let mut content_types = Vec::new();
+    for field in fields {
+        let attrs = PartAttrs::from_field(field)?;
+        let ty = &field.ty;
+        let name = field.ident.as_ref().unwrap().to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+
+        let schema = quote! { gen.subschema_for::<#ty>() };
+        let schema = match attrs.docs.text() {
+            Some(text) => quote! {
+                #macro_exports::patch_schema_data(#schema, |data| data.description = Some(#text.to_string()))
+            },
+            None => schema,
+        };
+        properties.push(quote! { (#name.to_string(), #schema) });
+        required.push(quote! {
+            if !<#ty as #axum_openapi::DescribeSchema>::is_optional() {
+                required.push(#name.to_string());
+            }
+        });
+        if let Some(content_type) = attrs.content_type {
+            content_types.push(quote! { (#name.to_string(), #content_type.to_string()) });
+        }
+    }
+
+    let ident = &input.ident;
+    Ok(quote! {
+        impl #impl_generics #axum_openapi::DescribeMultipart for #ident #ty_generics #where_clause {
+            fn describe_multipart(
+                gen: &mut #axum_openapi::SchemaGenerator,
+            ) -> #macro_exports::openapiv3::MediaType {
+                let mut required = Vec::new();
+                #(#required)*
+                let schema = #macro_exports::object_schema(vec![#(#properties),*], required);
+                #macro_exports::multipart_media_type(schema, vec![#(#content_types),*])
+            }
+        }
+    })
+}
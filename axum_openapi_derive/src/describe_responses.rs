@@ -0,0 +1,66 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+use crate::add_trait_bounds;
+use crate::attrs::ResponseAttrs;
+
+pub fn derive_responses(item: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(item)?;
+
+    let axum_openapi = quote!(axum_openapi);
+    let macro_exports = quote!(#axum_openapi::__macro);
+
+    let data = match &input.data {
+        syn::Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "DescribeResponses can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    add_trait_bounds(&mut generics, quote!(#axum_openapi::DescribeSchema));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let responses = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let attrs = ResponseAttrs::from_variant(variant)?;
+            let status = attrs.status;
+            let description = match attrs.docs.text() {
+                Some(text) => quote!(Some(#text)),
+                None => quote!(None),
+            };
+            let body = match &variant.fields {
+                Fields::Unit => quote!(None),
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let ty = &fields.unnamed[0].ty;
+                    quote!(Some(gen.subschema_for::<#ty>()))
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &variant.fields,
+                        "expected a unit variant or a newtype variant containing the body",
+                    ))
+                }
+            };
+            Ok(quote! { #macro_exports::response(#status, #description, #body) })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    Ok(quote! {
+        impl #impl_generics #axum_openapi::DescribeResponses for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn describe_responses(
+                gen: &mut #axum_openapi::SchemaGenerator,
+            ) -> Vec<(#macro_exports::openapiv3::StatusCode, #macro_exports::openapiv3::Response)> {
+                vec![#(#responses),*]
+            }
+        }
+    })
+}
@@ -1,34 +1,78 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DataStruct, DeriveInput};
+use syn::{DataEnum, DataStruct, DeriveInput, Fields};
+
+use crate::add_trait_bounds;
+use crate::attrs::{ContainerAttrs, Docs, FieldAttrs, Name, RenameAll, TagType, VariantAttrs};
+use crate::constraints::Constraints;
 
 struct Config<'a> {
     ident: &'a syn::Ident,
+    /// The generics of the type, with an added `DescribeSchema` bound on every type parameter
+    generics: syn::Generics,
+    attrs: ContainerAttrs,
     axum_openapi: TokenStream,
     macro_exports: TokenStream,
 }
 
 pub fn derive_schema(item: TokenStream) -> syn::Result<TokenStream> {
     let input: DeriveInput = syn::parse2(item)?;
+    let config = Config::new(&input)?;
 
-    let axum_openapi = quote!(axum_openapi);
-    let macro_exports = quote!(#axum_openapi::__macro);
+    if config.attrs.transparent {
+        return match &input.data {
+            syn::Data::Struct(data) => config.derive_transparent(data),
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[serde(transparent)] is only allowed on structs",
+            )),
+        };
+    }
 
-    let config = Config {
-        ident: &input.ident,
-        axum_openapi,
-        macro_exports,
+    let describe_schema = match &input.data {
+        syn::Data::Struct(data) => config.derive_schema_struct(data)?,
+        syn::Data::Enum(data) => config.derive_schema_enum(data)?,
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "unions are not supported",
+            ))
+        }
     };
 
-    match input.data {
-        syn::Data::Struct(data) => config.derive_schema_struct(data),
-        syn::Data::Enum(_) => todo!(),
-        syn::Data::Union(_) => todo!(),
-    }
+    let describe_schema = match (&config.attrs.docs.title, &config.attrs.docs.description) {
+        (None, None) => describe_schema,
+        _ => {
+            let docs = config.patch_docs(&config.attrs.docs, quote!(schema.schema_data));
+            quote! {
+                let mut schema = { #describe_schema };
+                #docs
+                schema
+            }
+        }
+    };
+
+    config.impl_describe_schema(describe_schema)
 }
 
-impl Config<'_> {
-    pub fn derive_schema_struct(&self, data: DataStruct) -> syn::Result<TokenStream> {
+impl<'a> Config<'a> {
+    fn new(input: &'a DeriveInput) -> syn::Result<Self> {
+        let axum_openapi = quote!(axum_openapi);
+        let macro_exports = quote!(#axum_openapi::__macro);
+
+        let mut generics = input.generics.clone();
+        add_trait_bounds(&mut generics, quote!(#axum_openapi::DescribeSchema));
+
+        Ok(Config {
+            ident: &input.ident,
+            generics,
+            attrs: ContainerAttrs::from_input(input)?,
+            axum_openapi,
+            macro_exports,
+        })
+    }
+
+    fn impl_describe_schema(&self, describe_schema: TokenStream) -> syn::Result<TokenStream> {
         let Config {
             ident,
             macro_exports,
@@ -36,49 +80,885 @@ impl Config<'_> {
             ..
         } = self;
         let openapiv3 = quote!(#macro_exports::openapiv3);
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
 
-        let properties = data.fields.iter().map(|field| {
-            let ty = &field.ty;
-            let name = field.ident.as_ref().expect("todo: tuple structs");
-            let name = name.to_string();
+        let ref_name = self.ref_name()?;
 
-            quote! {
-                (#name.to_string(), #openapiv3::ReferenceOr::Item(Box::new(<#ty as #axum_openapi::DescribeSchema>::describe_schema()))),
+        // generic types can only be collected once they are instantiated
+        let submit = match self.generics.params.is_empty() {
+            true => Some(quote! {
+                #[cfg(feature = "macro-based")]
+                #macro_exports::inventory::submit!(#![crate = #macro_exports] #macro_exports::SchemaDescription {
+                    schema: <#ident as #axum_openapi::DescribeSchema>::describe_schema(
+                        &mut #axum_openapi::SchemaGenerator::new(#axum_openapi::SchemaView::Response),
+                    ),
+                    name: #ref_name,
+                });
+            }),
+            false => None,
+        };
+
+        Ok(quote! {
+            impl #impl_generics #axum_openapi::DescribeSchema for #ident #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn describe_schema(gen: &mut #axum_openapi::SchemaGenerator) -> #openapiv3::Schema {
+                    #describe_schema
+                }
+
+                fn ref_name() -> Option<String> {
+                    Some(#ref_name)
+                }
             }
-        });
 
-        let ref_name = ident.to_string();
+            #submit
+        })
+    }
+
+    /// An expression for the component name.
+    ///
+    /// Generic types are named after their parameters, e.g. `Page_Pet` for `Page<Pet>`,
+    /// unless there is a `#[openapi(ref_name = "...")]` template like `"{T}Page"`.
+    fn ref_name(&self) -> syn::Result<TokenStream> {
+        let axum_openapi = &self.axum_openapi;
+
+        let param_name = |param: &syn::GenericParam| match param {
+            syn::GenericParam::Type(ty) => {
+                let ident = &ty.ident;
+                Some((
+                    ident.to_string(),
+                    quote! { <#ident as #axum_openapi::DescribeSchema>::type_name() },
+                ))
+            }
+            syn::GenericParam::Const(konst) => {
+                let ident = &konst.ident;
+                Some((ident.to_string(), quote! { #ident.to_string() }))
+            }
+            syn::GenericParam::Lifetime(_) => None,
+        };
+        let params: Vec<_> = self.generics.params.iter().filter_map(param_name).collect();
+
+        let template = match &self.attrs.ref_name {
+            Some(template) => template,
+            None if params.is_empty() => {
+                let name = &self.attrs.name.serialize;
+                return Ok(quote! { #name.to_string() });
+            }
+            None => {
+                let name = self
+                    .attrs
+                    .name
+                    .serialize
+                    .replace('{', "{{")
+                    .replace('}', "}}");
+                let format = std::iter::once(name)
+                    .chain(params.iter().map(|_| "{}".to_string()))
+                    .collect::<Vec<_>>()
+                    .join("_");
+                let args = params.iter().map(|(_, arg)| arg);
+                return Ok(quote! { format!(#format #(, #args)*) });
+            }
+        };
+
+        let mut format = String::new();
+        let mut args = Vec::new();
+        let mut rest = template.value();
+        while let Some(start) = rest.find(&['{', '}'][..]) {
+            let (literal, placeholder) = rest.split_at(start);
+            format.push_str(literal);
+            let end = match placeholder.find('}') {
+                Some(end) if placeholder.starts_with('{') => end,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        template,
+                        "unmatched brace in ref_name template",
+                    ))
+                }
+            };
+            let param = &placeholder[1..end];
+            match params.iter().find(|(name, _)| name == param) {
+                Some((_, arg)) => args.push(arg),
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        template,
+                        format!("`{{{}}}` is not a generic parameter of this type", param),
+                    ))
+                }
+            }
+            format.push_str("{}");
+            rest = placeholder[end + 1..].to_string();
+        }
+        format.push_str(&rest);
+
+        Ok(quote! { format!(#format #(, #args)*) })
+    }
+
+    /// `#[serde(transparent)]` structs are described exactly like their only field
+    fn derive_transparent(&self, data: &DataStruct) -> syn::Result<TokenStream> {
+        let Config {
+            ident,
+            macro_exports,
+            axum_openapi,
+            ..
+        } = self;
+        let openapiv3 = quote!(#macro_exports::openapiv3);
+        let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+
+        let mut fields = Vec::new();
+        for (i, field) in data.fields.iter().enumerate() {
+            if !FieldAttrs::from_field(field, i, RenameAll::NONE)?.skipped() {
+                fields.push(field);
+            }
+        }
+        let ty = match fields.as_slice() {
+            [field] => &field.ty,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &data.fields,
+                    "#[serde(transparent)] requires exactly one field that is not skipped",
+                ))
+            }
+        };
 
         Ok(quote! {
-            impl #axum_openapi::DescribeSchema for #ident {
-                fn describe_schema() -> #openapiv3::Schema {
-                    let properties = std::array::IntoIter::new([
-                        #(#properties)*
-                    ]).collect();
-                    let required = Vec::new();
-                    let obj = #openapiv3::ObjectType {
-                        properties,
-                        required,
-                        additional_properties: None,
-                        min_properties: None,
-                        max_properties: None,
-                    };
-                    #openapiv3::Schema {
-                        schema_data: Default::default(),
-                        schema_kind: #openapiv3::SchemaKind::Type(#openapiv3::Type::Object(obj)),
-                    }
+            impl #impl_generics #axum_openapi::DescribeSchema for #ident #ty_generics #where_clause {
+                fn describe_schema(gen: &mut #axum_openapi::SchemaGenerator) -> #openapiv3::Schema {
+                    <#ty as #axum_openapi::DescribeSchema>::describe_schema(gen)
                 }
 
                 fn ref_name() -> Option<String> {
-                    Some(#ref_name.to_string())
+                    <#ty as #axum_openapi::DescribeSchema>::ref_name()
+                }
+
+                fn is_optional() -> bool {
+                    <#ty as #axum_openapi::DescribeSchema>::is_optional()
+                }
+            }
+        })
+    }
+
+    fn derive_schema_struct(&self, data: &DataStruct) -> syn::Result<TokenStream> {
+        let macro_exports = &self.macro_exports;
+
+        match &data.fields {
+            Fields::Named(_) => {
+                self.fields_object(&data.fields, self.attrs.rename_all, Vec::new(), Vec::new())
+            }
+            // newtype structs serialize as their inner value
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let field = &unnamed.unnamed[0];
+                let attrs = FieldAttrs::from_field(field, 0, RenameAll::NONE)?;
+                let schema = self.field_property(field, &attrs)?;
+                Ok(quote! { #macro_exports::inline_schema(#schema) })
+            }
+            Fields::Unnamed(_) => {
+                let items = self.tuple_items(&data.fields)?;
+                Ok(quote! { #macro_exports::tuple_schema(vec![#(#items),*]) })
+            }
+            // unit structs serialize as `null`
+            Fields::Unit => Ok(quote! { #macro_exports::unit_schema() }),
+        }
+    }
+
+    fn derive_schema_enum(&self, data: &DataEnum) -> syn::Result<TokenStream> {
+        let macro_exports = &self.macro_exports;
+        let openapiv3 = quote!(#macro_exports::openapiv3);
+
+        let item = |schema: TokenStream| quote! { #openapiv3::ReferenceOr::Item(#schema) };
+
+        let mut variants = Vec::new();
+        for variant in &data.variants {
+            let attrs = VariantAttrs::from_variant(variant, &self.attrs)?;
+            if !attrs.skip_serializing {
+                variants.push((variant, attrs));
+            }
+        }
+
+        // Tagged variants are told apart by their `enum`-constrained tag property. There is no `discriminator`,
+        // because OpenAPI only defines one over `$ref`erenced alternatives, while the variants are inline schemas.
+        let variants = match &self.attrs.tag {
+            TagType::External => {
+                let (unit, data): (Vec<_>, Vec<_>) = variants
+                    .iter()
+                    .partition(|(variant, _)| matches!(variant.fields, Fields::Unit));
+                let unit_names: Vec<_> = unit.iter().map(|(_, attrs)| &attrs.name).collect();
+                let unit_values = self.enum_values(unit_names.iter().copied());
+
+                if data.is_empty() {
+                    return Ok(quote! { #macro_exports::string_enum_schema(#unit_values) });
+                }
+
+                let mut variants = Vec::new();
+                if !unit_names.is_empty() {
+                    variants
+                        .push(item(quote! { #macro_exports::string_enum_schema(#unit_values) }));
+                }
+                for (variant, attrs) in data {
+                    let name = self.name(&attrs.name);
+                    let content = self.variant_content(&variant.fields, attrs)?;
+                    let object = quote! {
+                        #macro_exports::object_schema(vec![(#name.to_string(), #content)], vec![#name.to_string()])
+                    };
+                    variants.push(item(self.with_aliases(object, Some(&attrs.name))));
                 }
+
+                variants
+            }
+            TagType::Internal { tag } => {
+                variants
+                    .iter()
+                    .map(|(variant, attrs)| {
+                        let tag_values = self.enum_values(Some(&attrs.name));
+                        let tag_property = quote! {
+                            (#tag.to_string(), #openapiv3::ReferenceOr::Item(#macro_exports::string_enum_schema(#tag_values)))
+                        };
+
+                        let schema = match &variant.fields {
+                            Fields::Unit => quote! {
+                                #macro_exports::object_schema(vec![#tag_property], vec![#tag.to_string()])
+                            },
+                            Fields::Named(_) => self.fields_object(
+                                &variant.fields,
+                                attrs.rename_all,
+                                vec![tag_property],
+                                vec![quote! { #tag.to_string() }],
+                            )?,
+                            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                                let field = &fields.unnamed[0];
+                                let inner = self.field_property(
+                                    field,
+                                    &FieldAttrs::from_field(field, 0, RenameAll::NONE)?,
+                                )?;
+                                quote! {
+                                    #macro_exports::all_of_schema(vec![
+                                        #openapiv3::ReferenceOr::Item(#macro_exports::object_schema(vec![#tag_property], vec![#tag.to_string()])),
+                                        #inner,
+                                    ])
+                                }
+                            }
+                            Fields::Unnamed(fields) => {
+                                return Err(syn::Error::new_spanned(
+                                    fields,
+                                    "internally tagged enums cannot contain tuple variants",
+                                ))
+                            }
+                        };
+                        Ok(item(schema))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+            }
+            TagType::Adjacent { tag, content } => {
+                variants
+                    .iter()
+                    .map(|(variant, attrs)| {
+                        let tag_values = self.enum_values(Some(&attrs.name));
+                        let tag_property = quote! {
+                            (#tag.to_string(), #openapiv3::ReferenceOr::Item(#macro_exports::string_enum_schema(#tag_values)))
+                        };
+
+                        let schema = match &variant.fields {
+                            Fields::Unit => quote! {
+                                #macro_exports::object_schema(vec![#tag_property], vec![#tag.to_string()])
+                            },
+                            fields => {
+                                let content_schema = self.variant_content(fields, attrs)?;
+                                quote! {
+                                    #macro_exports::object_schema(
+                                        vec![#tag_property, (#content.to_string(), #content_schema)],
+                                        vec![#tag.to_string(), #content.to_string()],
+                                    )
+                                }
+                            }
+                        };
+                        Ok(item(schema))
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+            }
+            TagType::None => {
+                variants
+                    .iter()
+                    .map(|(variant, attrs)| match &variant.fields {
+                        Fields::Unit => Ok(item(quote! { #macro_exports::unit_schema() })),
+                        fields => self.variant_content(fields, attrs),
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?
+            }
+        };
+
+        Ok(quote! { #macro_exports::one_of_schema(vec![#(#variants),*]) })
+    }
+
+    /// The data of a non-unit enum variant as a `ReferenceOr<Schema>`
+    fn variant_content(&self, fields: &Fields, attrs: &VariantAttrs) -> syn::Result<TokenStream> {
+        let macro_exports = &self.macro_exports;
+        let openapiv3 = quote!(#macro_exports::openapiv3);
+
+        Ok(match fields {
+            Fields::Named(_) => {
+                let object =
+                    self.fields_object(fields, attrs.rename_all, Vec::new(), Vec::new())?;
+                quote! { #openapiv3::ReferenceOr::Item(#object) }
             }
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+                let field = &unnamed.unnamed[0];
+                self.field_property(field, &FieldAttrs::from_field(field, 0, RenameAll::NONE)?)?
+            }
+            Fields::Unnamed(_) => {
+                let items = self.tuple_items(fields)?;
+                quote! {
+                    #openapiv3::ReferenceOr::Item(#macro_exports::tuple_schema(vec![#(#items),*]))
+                }
+            }
+            Fields::Unit => {
+                return Err(syn::Error::new_spanned(
+                    fields,
+                    "unit variants have no content",
+                ))
+            }
+        })
+    }
 
-            #[cfg(feature = "macro-based")]
-            #macro_exports::inventory::submit!(#![crate = #macro_exports] #macro_exports::SchemaDescription {
-                schema: <#ident as #axum_openapi::DescribeSchema>::describe_schema(),
-                name: #ref_name.to_string(),
+    /// An object schema with a property for every named field which is not skipped, following the leading `properties` and `required`.
+    ///
+    /// Fields which are only serialized are marked as `readOnly`, fields which are only deserialized as `writeOnly`.
+    /// `#[serde(flatten)]` fields are merged into the object.
+    /// Which fields are required is decided at runtime, see [`Config::optional_field`].
+    fn fields_object(
+        &self,
+        fields: &Fields,
+        rename_all: RenameAll,
+        mut properties: Vec<TokenStream>,
+        required: Vec<TokenStream>,
+    ) -> syn::Result<TokenStream> {
+        let macro_exports = &self.macro_exports;
+
+        let mut flattened = Vec::new();
+        let mut push_required = Vec::new();
+        let mut aliased = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            let attrs = FieldAttrs::from_field(field, i, rename_all)?;
+            if attrs.skipped() {
+                continue;
+            }
+            let optional = self.optional_field(&field.ty, &attrs);
+            if attrs.flatten {
+                if !Constraints::from_attrs(&field.attrs)?.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "#[openapi(...)] constraints cannot be used on flattened fields",
+                    ));
+                }
+                let schema = self.field_schema(&field.ty);
+                let optional = optional.unwrap_or_else(|| quote!(true));
+                flattened.push(quote! { (#schema, #optional) });
+                continue;
+            }
+
+            let mut schema = self.field_property(field, &attrs)?;
+            let name = if attrs.skip_serializing {
+                schema = quote! { #macro_exports::patch_schema_data(#schema, |data| data.write_only = true) };
+                let name = &attrs.name.deserialize;
+                quote!(#name)
+            } else if attrs.skip_deserializing {
+                schema = quote! { #macro_exports::patch_schema_data(#schema, |data| data.read_only = true) };
+                let name = &attrs.name.serialize;
+                quote!(#name)
+            } else {
+                self.name(&attrs.name)
+            };
+            if !attrs.skip_deserializing {
+                aliased.push(attrs.name);
+            }
+
+            properties.push(quote! { (#name.to_string(), #schema) });
+            if let Some(optional) = optional {
+                push_required.push(quote! {
+                    if !(#optional) {
+                        required.push(#name.to_string());
+                    }
+                });
+            }
+        }
+
+        let required = match push_required.is_empty() {
+            true => quote! { vec![#(#required),*] },
+            false => quote! {{
+                let mut required: Vec<String> = vec![#(#required),*];
+                #(#push_required)*
+                required
+            }},
+        };
+        let object = quote! { #macro_exports::object_schema(vec![#(#properties),*], #required) };
+        let object = self.with_aliases(object, &aliased);
+        Ok(match flattened.is_empty() {
+            true => object,
+            false => quote! { #macro_exports::flatten_schema(#object, vec![#(#flattened),*]) },
+        })
+    }
+
+    /// The elements of a tuple struct or variant which are not skipped
+    fn tuple_items(&self, fields: &Fields) -> syn::Result<Vec<TokenStream>> {
+        let mut items = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            let attrs = FieldAttrs::from_field(field, i, RenameAll::NONE)?;
+            if !attrs.skipped() {
+                items.push(self.field_property(field, &attrs)?);
+            }
+        }
+        Ok(items)
+    }
+
+    /// The schema of a field with its `#[openapi(...)]` constraints and doc comments
+    fn field_property(&self, field: &syn::Field, attrs: &FieldAttrs) -> syn::Result<TokenStream> {
+        let macro_exports = &self.macro_exports;
+
+        let constraints = Constraints::from_attrs(&field.attrs)?;
+        let mut schema = match constraints.is_empty() {
+            true => self.field_schema(&field.ty),
+            // constraints can only be added to an inline schema
+            false => {
+                let ty = &field.ty;
+                let axum_openapi = &self.axum_openapi;
+                let openapiv3 = quote!(#macro_exports::openapiv3);
+                let schema = quote! {
+                    #openapiv3::ReferenceOr::Item(<#ty as #axum_openapi::DescribeSchema>::describe_schema(gen))
+                };
+                constraints.apply(ty, schema, macro_exports)
+            }
+        };
+        if attrs.docs.title.is_some() || attrs.docs.description.is_some() {
+            let docs = self.patch_docs(&attrs.docs, quote!((*data)));
+            schema = quote! { #macro_exports::patch_schema_data(#schema, |data| { #docs }) };
+        }
+        Ok(schema)
+    }
+
+    /// A boolean expression telling whether the field may be missing in the view of the generator,
+    /// or `None` if it is never required.
+    ///
+    /// Fields with a default may be missing when deserializing, fields with `skip_serializing_if` when serializing
+    /// and types like `Option` in both directions.
+    fn optional_field(&self, ty: &syn::Type, attrs: &FieldAttrs) -> Option<TokenStream> {
+        let axum_openapi = &self.axum_openapi;
+        let view = quote!(#axum_openapi::SchemaView);
+
+        let default = attrs.default || self.attrs.default;
+        let condition = match (default, attrs.skip_serializing_if) {
+            (true, true) => return None,
+            (true, false) => quote! { || gen.view() == #view::Request },
+            (false, true) => quote! { || gen.view() == #view::Response },
+            (false, false) => quote! {},
+        };
+        Some(quote! { <#ty as #axum_openapi::DescribeSchema>::is_optional() #condition })
+    }
+
+    /// A `&str` expression for a field or variant name.
+    ///
+    /// Serde reads requests using the deserialize name and writes responses using the serialize name,
+    /// so if they differ the name is picked from the view of the generator.
+    fn name(&self, name: &Name) -> TokenStream {
+        let Name {
+            serialize,
+            deserialize,
+            ..
+        } = name;
+        if serialize == deserialize {
+            return quote!(#serialize);
+        }
+        let axum_openapi = &self.axum_openapi;
+        quote! {
+            match gen.view() {
+                #axum_openapi::SchemaView::Request => #deserialize,
+                #axum_openapi::SchemaView::Response => #serialize,
+            }
+        }
+    }
+
+    /// A `&[&str]` expression of the values of a string enum, which also accepts the aliases of the names in requests
+    fn enum_values<'n>(&self, names: impl IntoIterator<Item = &'n Name>) -> TokenStream {
+        let names: Vec<_> = names.into_iter().collect();
+        if names.iter().all(|name| name.aliases.is_empty()) {
+            let values = names.iter().map(|name| self.name(name));
+            return quote! { &[#(#values),*] };
+        }
+        let axum_openapi = &self.axum_openapi;
+        let serialize = names.iter().map(|name| &name.serialize);
+        let deserialize = names.iter().map(|name| &name.deserialize);
+        let aliases = names.iter().flat_map(|name| &name.aliases);
+        quote! {
+            &match gen.view() {
+                #axum_openapi::SchemaView::Request => vec![#(#deserialize,)* #(#aliases),*],
+                #axum_openapi::SchemaView::Response => vec![#(#serialize),*],
+            }
+        }
+    }
+
+    /// Adds the aliases of the property `names` to the `object` schema expression in requests, see `alias_properties`
+    fn with_aliases<'n>(
+        &self,
+        object: TokenStream,
+        names: impl IntoIterator<Item = &'n Name>,
+    ) -> TokenStream {
+        let aliases: Vec<_> = names
+            .into_iter()
+            .flat_map(|name| {
+                let deserialize = &name.deserialize;
+                name.aliases
+                    .iter()
+                    .map(move |alias| quote! { (#deserialize, #alias) })
+            })
+            .collect();
+        if aliases.is_empty() {
+            return object;
+        }
+        let axum_openapi = &self.axum_openapi;
+        let macro_exports = &self.macro_exports;
+        quote! {{
+            let object = #object;
+            match gen.view() {
+                #axum_openapi::SchemaView::Request => #macro_exports::alias_properties(object, &[#(#aliases),*]),
+                #axum_openapi::SchemaView::Response => object,
+            }
+        }}
+    }
+
+    /// Statements setting the title and description from the doc comments on the `SchemaData` expression `schema_data`.
+    ///
+    /// The description is appended to the one of the schema, which may already describe the type.
+    fn patch_docs(&self, docs: &Docs, schema_data: TokenStream) -> TokenStream {
+        let macro_exports = &self.macro_exports;
+        let title = docs
+            .title
+            .as_ref()
+            .map(|title| quote! { #schema_data.title = Some(#title.to_string()); });
+        let description = docs.description.as_ref().map(|description| {
+            quote! { #macro_exports::append_description(&mut #schema_data, #description); }
+        });
+        quote! { #title #description }
+    }
+
+    /// The schema of a field of type `ty` as a `ReferenceOr<Schema>`, which is referenced if it has a `ref_name`
+    fn field_schema(&self, ty: &syn::Type) -> TokenStream {
+        quote! { gen.subschema_for::<#ty>() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_schema, Config};
+    use pretty_assertions::assert_eq;
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    /// The schema expression derived for an enum
+    fn enum_schema(item: TokenStream) -> String {
+        let input = syn::parse2(item).unwrap();
+        let config = Config::new(&input).unwrap();
+        match &input.data {
+            syn::Data::Enum(data) => config.derive_schema_enum(data).unwrap().to_string(),
+            _ => panic!("expected an enum"),
+        }
+    }
+
+    /// The schema expression derived for a struct
+    fn struct_schema(item: TokenStream) -> String {
+        let input = syn::parse2(item).unwrap();
+        let config = Config::new(&input).unwrap();
+        match &input.data {
+            syn::Data::Struct(data) => config.derive_schema_struct(data).unwrap().to_string(),
+            _ => panic!("expected a struct"),
+        }
+    }
+
+    /// The component name expression derived for a type, or the error message
+    fn ref_name(item: TokenStream) -> Result<String, String> {
+        let input = syn::parse2(item).unwrap();
+        let config = Config::new(&input).unwrap();
+        match config.ref_name() {
+            Ok(ref_name) => Ok(ref_name.to_string()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    #[test]
+    fn ref_name_templates_substitute_parameters() {
+        let name = ref_name(quote! {
+            #[openapi(ref_name = "{T}Page{N}")]
+            struct Page<'a, T, const N: usize>(&'a [T; N]);
+        });
+        let expected = quote! {
+            format!("{}Page{}", <T as axum_openapi::DescribeSchema>::type_name(), N.to_string())
+        };
+        assert_eq!(name, Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn ref_name_templates_without_placeholders() {
+        let name = ref_name(quote! {
+            #[openapi(ref_name = "Pets")]
+            struct Page<T>(Vec<T>);
+        });
+        assert_eq!(name, Ok(quote! { format!("Pets") }.to_string()));
+    }
+
+    #[test]
+    fn ref_name_templates_with_unknown_parameters() {
+        let name = ref_name(quote! {
+            #[openapi(ref_name = "{U}Page")]
+            struct Page<T>(Vec<T>);
+        });
+        assert_eq!(
+            name,
+            Err("`{U}` is not a generic parameter of this type".to_string())
+        );
+    }
+
+    #[test]
+    fn ref_name_templates_with_unmatched_braces() {
+        for template in &["{TPage", "T}Page", "{T}}Page"] {
+            let name = ref_name(quote! {
+                #[openapi(ref_name = #template)]
+                struct Page<T>(Vec<T>);
             });
+            assert_eq!(
+                name,
+                Err("unmatched brace in ref_name template".to_string()),
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn split_renames_depend_on_the_view() {
+        let schema = struct_schema(quote! {
+            #[serde(rename_all(deserialize = "camelCase"))]
+            struct In {
+                user_name: String,
+                #[serde(rename(deserialize = "X"))]
+                other: i32,
+            }
+        });
+        let user_name = quote! {
+            match gen.view() {
+                axum_openapi::SchemaView::Request => "userName",
+                axum_openapi::SchemaView::Response => "user_name",
+            }
+        };
+        let other = quote! {
+            match gen.view() {
+                axum_openapi::SchemaView::Request => "X",
+                axum_openapi::SchemaView::Response => "other",
+            }
+        };
+        let expected = quote! {
+            axum_openapi::__macro::object_schema(
+                vec![
+                    (#user_name.to_string(), gen.subschema_for::<String>()),
+                    (#other.to_string(), gen.subschema_for::<i32>())
+                ],
+                {
+                    let mut required: Vec<String> = vec![];
+                    if !(<String as axum_openapi::DescribeSchema>::is_optional()) {
+                        required.push(#user_name.to_string());
+                    }
+                    if !(<i32 as axum_openapi::DescribeSchema>::is_optional()) {
+                        required.push(#other.to_string());
+                    }
+                    required
+                }
+            )
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn split_variant_renames_depend_on_the_view() {
+        let schema = enum_schema(quote! {
+            enum Kind {
+                #[serde(rename(serialize = "dog", deserialize = "DOG"))]
+                Dog,
+                Cat,
+            }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::string_enum_schema(&[
+                match gen.view() {
+                    axum_openapi::SchemaView::Request => "DOG",
+                    axum_openapi::SchemaView::Response => "dog",
+                },
+                "Cat"
+            ])
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn macro_doc_attributes_are_skipped() {
+        let with_macros = derive_schema(quote! {
+            /// A user
+            #[doc = include_str!("user.md")]
+            struct User {
+                /// The name
+                #[doc = concat!("of ", "the user")]
+                name: String,
+            }
+        })
+        .unwrap();
+        let without_macros = derive_schema(quote! {
+            /// A user
+            struct User {
+                /// The name
+                name: String,
+            }
+        })
+        .unwrap();
+        assert_eq!(with_macros.to_string(), without_macros.to_string());
+    }
+
+    #[test]
+    fn field_aliases_are_properties_of_requests() {
+        let schema = struct_schema(quote! {
+            struct In {
+                #[serde(alias = "userName", alias = "login")]
+                user_name: String,
+            }
+        });
+        let expected = quote! {{
+            let object = axum_openapi::__macro::object_schema(
+                vec![("user_name".to_string(), gen.subschema_for::<String>())],
+                {
+                    let mut required: Vec<String> = vec![];
+                    if !(<String as axum_openapi::DescribeSchema>::is_optional()) {
+                        required.push("user_name".to_string());
+                    }
+                    required
+                }
+            );
+            match gen.view() {
+                axum_openapi::SchemaView::Request => axum_openapi::__macro::alias_properties(
+                    object,
+                    &[("user_name", "userName"), ("user_name", "login")]
+                ),
+                axum_openapi::SchemaView::Response => object,
+            }
+        }};
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn variant_aliases_are_values_of_requests() {
+        let schema = enum_schema(quote! {
+            enum Kind {
+                #[serde(alias = "doggo")]
+                Dog,
+                Cat,
+            }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::string_enum_schema(&match gen.view() {
+                axum_openapi::SchemaView::Request => vec!["Dog", "Cat", "doggo"],
+                axum_openapi::SchemaView::Response => vec!["Dog", "Cat"],
+            })
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn externally_tagged() {
+        let schema = enum_schema(quote! {
+            enum Pet { Unknown, Dog(Dog) }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::one_of_schema(vec![
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::string_enum_schema(&["Unknown"])),
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::object_schema(
+                    vec![("Dog".to_string(), gen.subschema_for::<Dog>())],
+                    vec!["Dog".to_string()]
+                ))
+            ])
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn externally_tagged_unit_variants() {
+        let schema = enum_schema(quote! {
+            #[serde(rename_all = "lowercase")]
+            enum Kind { Dog, Cat }
+        });
+        let expected = quote! { axum_openapi::__macro::string_enum_schema(&["dog", "cat"]) };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn internally_tagged() {
+        let schema = enum_schema(quote! {
+            #[serde(tag = "type")]
+            enum Pet { Unknown, Dog(Dog) }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::one_of_schema(vec![
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::object_schema(
+                    vec![("type".to_string(), axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::string_enum_schema(&["Unknown"])))],
+                    vec!["type".to_string()]
+                )),
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::all_of_schema(vec![
+                    axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::object_schema(
+                        vec![("type".to_string(), axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::string_enum_schema(&["Dog"])))],
+                        vec!["type".to_string()]
+                    )),
+                    gen.subschema_for::<Dog>(),
+                ]))
+            ])
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn adjacently_tagged() {
+        let schema = enum_schema(quote! {
+            #[serde(tag = "t", content = "c")]
+            enum Pet { Unknown, Dog(Dog) }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::one_of_schema(vec![
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::object_schema(
+                    vec![("t".to_string(), axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::string_enum_schema(&["Unknown"])))],
+                    vec!["t".to_string()]
+                )),
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::object_schema(
+                    vec![
+                        ("t".to_string(), axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::string_enum_schema(&["Dog"]))),
+                        ("c".to_string(), gen.subschema_for::<Dog>())
+                    ],
+                    vec!["t".to_string(), "c".to_string()],
+                ))
+            ])
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn untagged() {
+        let schema = enum_schema(quote! {
+            #[serde(untagged)]
+            enum Pet { Unknown, Dog(Dog) }
+        });
+        let expected = quote! {
+            axum_openapi::__macro::one_of_schema(vec![
+                axum_openapi::__macro::openapiv3::ReferenceOr::Item(axum_openapi::__macro::unit_schema()),
+                gen.subschema_for::<Dog>()
+            ])
+        };
+        assert_eq!(schema, expected.to_string());
+    }
+
+    #[test]
+    fn unions_are_rejected() {
+        let error = derive_schema(quote! {
+            union Bits { int: u32, float: f32 }
         })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "unions are not supported");
     }
 }
@@ -1,4 +1,9 @@
 use proc_macro::TokenStream;
+mod attrs;
+mod case;
+mod constraints;
+mod describe_multipart;
+mod describe_responses;
 mod describe_schema;
 
 #[cfg(feature = "macro-based")]
@@ -6,13 +11,27 @@ mod handler;
 #[cfg(feature = "macro-based")]
 mod routes;
 
-#[proc_macro_derive(DescribeSchema)]
+#[proc_macro_derive(DescribeSchema, attributes(openapi))]
 pub fn derive_answer_fn(item: TokenStream) -> TokenStream {
     describe_schema::derive_schema(item.into())
         .unwrap_or_else(|e| e.into_compile_error())
         .into()
 }
 
+#[proc_macro_derive(DescribeMultipart, attributes(openapi))]
+pub fn derive_describe_multipart(item: TokenStream) -> TokenStream {
+    describe_multipart::derive_multipart(item.into())
+        .unwrap_or_else(|e| e.into_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(DescribeResponses, attributes(openapi))]
+pub fn derive_describe_responses(item: TokenStream) -> TokenStream {
+    describe_responses::derive_responses(item.into())
+        .unwrap_or_else(|e| e.into_compile_error())
+        .into()
+}
+
 #[proc_macro_attribute]
 #[cfg(feature = "macro-based")]
 pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -29,6 +48,20 @@ pub fn routes(tokens: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Adds the `bound`, like `axum_openapi::DescribeSchema`, to every type parameter of a deriving type
+fn add_trait_bounds(generics: &mut syn::Generics, bound: proc_macro2::TokenStream) {
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#param: #bound));
+    }
+}
+
 use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
@@ -1,26 +1,141 @@
 use openapiv3::*;
 
-use crate::openapi_traits::DescribeSchema;
+use crate::openapi_traits::{
+    ArraySchema, DescribeSchema, Number, NumberConstraints, NumberSchema, StringSchema,
+};
+use crate::schema_generator::SchemaGenerator;
 use crate::utils;
 
-impl DescribeSchema for i32 {
-    fn describe_schema() -> Schema {
-        utils::ty_schema(Type::Integer(IntegerType {
-            format: VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32),
-            ..Default::default()
-        }))
+macro_rules! impl_integer {
+    ( $($ty:ty => $format:literal, minimum: $minimum:expr;)* ) => {
+        $(
+            impl DescribeSchema for $ty {
+                fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+                    utils::ty_schema(Type::Integer(IntegerType {
+                        format: utils::integer_format($format),
+                        minimum: $minimum,
+                        ..Default::default()
+                    }))
+                }
+            }
+            impl NumberSchema for $ty {
+                type Number = i64;
+            }
+        )*
+    };
+}
+impl_integer! {
+    i8 => "int8", minimum: None;
+    i16 => "int16", minimum: None;
+    i32 => "int32", minimum: None;
+    i64 => "int64", minimum: None;
+    i128 => "int128", minimum: None;
+    isize => "int64", minimum: None;
+    u8 => "uint8", minimum: Some(0);
+    u16 => "uint16", minimum: Some(0);
+    u32 => "uint32", minimum: Some(0);
+    u64 => "uint64", minimum: Some(0);
+    u128 => "uint128", minimum: Some(0);
+    usize => "uint64", minimum: Some(0);
+    std::num::NonZeroI8 => "int8", minimum: None;
+    std::num::NonZeroI16 => "int16", minimum: None;
+    std::num::NonZeroI32 => "int32", minimum: None;
+    std::num::NonZeroI64 => "int64", minimum: None;
+    std::num::NonZeroI128 => "int128", minimum: None;
+    std::num::NonZeroIsize => "int64", minimum: None;
+    std::num::NonZeroU8 => "uint8", minimum: Some(1);
+    std::num::NonZeroU16 => "uint16", minimum: Some(1);
+    std::num::NonZeroU32 => "uint32", minimum: Some(1);
+    std::num::NonZeroU64 => "uint64", minimum: Some(1);
+    std::num::NonZeroU128 => "uint128", minimum: Some(1);
+    std::num::NonZeroUsize => "uint64", minimum: Some(1);
+}
+
+/// Types which are serialized as a string, optionally with a `format`
+macro_rules! impl_string {
+    ( $( [$($generics:tt)*] $ty:ty $(=> $format:literal)?; )* ) => {
+        $(
+            impl<$($generics)*> $crate::openapi_traits::DescribeSchema for $ty {
+                fn describe_schema(
+                    _: &mut $crate::schema_generator::SchemaGenerator,
+                ) -> ::openapiv3::Schema {
+                    $crate::utils::string_schema(None $(.or(Some($format)))?)
+                }
+            }
+            impl<$($generics)*> $crate::openapi_traits::StringSchema for $ty {}
+        )*
+    };
+}
+
+impl_string! {
+    [] std::path::Path;
+    [] std::path::PathBuf;
+    [] std::net::Ipv4Addr => "ipv4";
+    [] std::net::Ipv6Addr => "ipv6";
+    [] std::net::SocketAddr;
+    [] std::net::SocketAddrV4;
+    [] std::net::SocketAddrV6;
+}
+
+impl DescribeSchema for std::net::IpAddr {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::one_of_schema(vec![
+            gen.subschema_for::<std::net::Ipv4Addr>(),
+            gen.subschema_for::<std::net::Ipv6Addr>(),
+        ])
     }
 }
-impl DescribeSchema for i64 {
-    fn describe_schema() -> Schema {
-        utils::ty_schema(Type::Integer(IntegerType {
-            format: VariantOrUnknownOrEmpty::Item(IntegerFormat::Int64),
-            ..Default::default()
-        }))
+
+/// `{ "secs": u64, "nanos": u32 }`
+impl DescribeSchema for std::time::Duration {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::object_schema(
+            vec![
+                ("secs".to_string(), gen.subschema_for::<u64>()),
+                ("nanos".to_string(), gen.subschema_for::<u32>()),
+            ],
+            vec!["secs".to_string(), "nanos".to_string()],
+        )
+    }
+}
+
+/// `{ "secs_since_epoch": u64, "nanos_since_epoch": u32 }`
+impl DescribeSchema for std::time::SystemTime {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::object_schema(
+            vec![
+                ("secs_since_epoch".to_string(), gen.subschema_for::<u64>()),
+                ("nanos_since_epoch".to_string(), gen.subschema_for::<u32>()),
+            ],
+            vec![
+                "secs_since_epoch".to_string(),
+                "nanos_since_epoch".to_string(),
+            ],
+        )
     }
 }
+
+impl DescribeSchema for () {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::unit_schema()
+    }
+}
+
+/// Tuples are arrays of a fixed length, whose items are one of the element schemas
+macro_rules! impl_tuple {
+    ( $($ty:ident),* ) => {
+        impl<$($ty: DescribeSchema),*> DescribeSchema for ($($ty,)*) {
+            fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+                utils::tuple_schema(vec![$(gen.subschema_for::<$ty>()),*])
+            }
+        }
+        impl<$($ty: DescribeSchema),*> ArraySchema for ($($ty,)*) {}
+    };
+}
+axum_openapi_derive::all_tuples!(impl_tuple, 1, 16, T);
+
 impl DescribeSchema for f32 {
-    fn describe_schema() -> Schema {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
         utils::ty_schema(Type::Number(NumberType {
             format: VariantOrUnknownOrEmpty::Item(NumberFormat::Float),
             ..Default::default()
@@ -28,7 +143,7 @@ impl DescribeSchema for f32 {
     }
 }
 impl DescribeSchema for f64 {
-    fn describe_schema() -> Schema {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
         utils::ty_schema(Type::Number(NumberType {
             format: VariantOrUnknownOrEmpty::Item(NumberFormat::Double),
             ..Default::default()
@@ -36,24 +151,33 @@ impl DescribeSchema for f64 {
     }
 }
 impl DescribeSchema for bool {
-    fn describe_schema() -> Schema {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
         utils::ty_schema(Type::Boolean {})
     }
 }
 impl DescribeSchema for String {
-    fn describe_schema() -> Schema {
-        utils::ty_schema(Type::String(StringType::default()))
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::string_schema(None)
+    }
+}
+impl DescribeSchema for str {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::string_schema(None)
     }
 }
-impl DescribeSchema for &str {
-    fn describe_schema() -> Schema {
-        utils::ty_schema(Type::String(StringType::default()))
+impl DescribeSchema for char {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::ty_schema(Type::String(StringType {
+            min_length: Some(1),
+            max_length: Some(1),
+            ..Default::default()
+        }))
     }
 }
 impl<T: DescribeSchema> DescribeSchema for Vec<T> {
-    fn describe_schema() -> Schema {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
         utils::ty_schema(Type::Array(ArrayType {
-            items: ReferenceOr::Item(Box::new(T::describe_schema())),
+            items: utils::boxed(gen.subschema_for::<T>()),
             min_items: None,
             max_items: None,
             unique_items: false,
@@ -61,20 +185,141 @@ impl<T: DescribeSchema> DescribeSchema for Vec<T> {
     }
 }
 impl<T: DescribeSchema, const N: usize> DescribeSchema for [T; N] {
-    fn describe_schema() -> Schema {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
         utils::ty_schema(Type::Array(ArrayType {
-            items: ReferenceOr::Item(Box::new(T::describe_schema())),
+            items: utils::boxed(gen.subschema_for::<T>()),
             min_items: Some(N),
             max_items: Some(N),
             unique_items: false,
         }))
     }
 }
+impl<T: DescribeSchema> DescribeSchema for std::collections::VecDeque<T> {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        Vec::<T>::describe_schema(gen)
+    }
+}
+impl<T: DescribeSchema, S> DescribeSchema for std::collections::HashSet<T, S> {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::set_schema(gen.subschema_for::<T>())
+    }
+}
+impl<T: DescribeSchema> DescribeSchema for std::collections::BTreeSet<T> {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::set_schema(gen.subschema_for::<T>())
+    }
+}
+impl<K: DescribeSchema, V: DescribeSchema, S> DescribeSchema
+    for std::collections::HashMap<K, V, S>
+{
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::map_schema(K::describe_schema(gen), gen.subschema_for::<V>())
+    }
+}
+impl<K: DescribeSchema, V: DescribeSchema> DescribeSchema for std::collections::BTreeMap<K, V> {
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::map_schema(K::describe_schema(gen), gen.subschema_for::<V>())
+    }
+}
 
 impl<T: DescribeSchema> DescribeSchema for Option<T> {
-    fn describe_schema() -> Schema {
-        let mut schema = T::describe_schema();
-        schema.schema_data.nullable = true;
-        schema
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+        utils::inline_schema(utils::patch_schema_data(gen.subschema_for::<T>(), |data| {
+            data.nullable = true
+        }))
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+/// Wrappers which are described exactly like the type they contain, including its `ref_name`
+macro_rules! impl_transparent {
+    ( $( [$($generics:tt)*] $ty:ty => $t:ident; )* ) => {
+        $(
+            impl<$($generics)*> DescribeSchema for $ty
+            where
+                $t: DescribeSchema,
+            {
+                fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+                    $t::describe_schema(gen)
+                }
+                fn ref_name() -> Option<String> {
+                    $t::ref_name()
+                }
+                fn type_name() -> String {
+                    $t::type_name()
+                }
+                fn is_optional() -> bool {
+                    $t::is_optional()
+                }
+            }
+            impl<$($generics)*> StringSchema for $ty where $t: StringSchema {}
+            impl<$($generics)*> NumberSchema for $ty
+            where
+                $t: NumberSchema,
+            {
+                type Number = $t::Number;
+            }
+            impl<$($generics)*> ArraySchema for $ty where $t: ArraySchema {}
+        )*
+    };
+}
+impl_transparent! {
+    ['a, T: ?Sized] &'a T => T;
+    [T: ?Sized] Box<T> => T;
+    [T: ?Sized] std::rc::Rc<T> => T;
+    [T: ?Sized] std::sync::Arc<T> => T;
+    ['a, T: ToOwned + ?Sized] std::borrow::Cow<'a, T> => T;
+    [T] std::num::Wrapping<T> => T;
+    [T] std::cmp::Reverse<T> => T;
+}
+
+impl NumberSchema for f32 {
+    type Number = f64;
+}
+impl NumberSchema for f64 {
+    type Number = f64;
+}
+impl StringSchema for String {}
+impl StringSchema for str {}
+impl StringSchema for char {}
+impl<T: DescribeSchema> ArraySchema for Vec<T> {}
+impl<T: DescribeSchema, const N: usize> ArraySchema for [T; N] {}
+impl<T: DescribeSchema> ArraySchema for std::collections::VecDeque<T> {}
+impl<T: DescribeSchema, S> ArraySchema for std::collections::HashSet<T, S> {}
+impl<T: DescribeSchema> ArraySchema for std::collections::BTreeSet<T> {}
+
+impl<T: NumberSchema> NumberSchema for Option<T> {
+    type Number = T::Number;
+}
+impl<T: StringSchema> StringSchema for Option<T> {}
+impl<T: ArraySchema> ArraySchema for Option<T> {}
+
+impl Number for i64 {
+    fn patch_type(ty: &mut Type, constraints: NumberConstraints<Self>) {
+        let integer = match ty {
+            Type::Integer(integer) => integer,
+            _ => panic!("integer constraints on a schema which is not an integer"),
+        };
+        integer.minimum = constraints.minimum.or(integer.minimum);
+        integer.maximum = constraints.maximum.or(integer.maximum);
+        integer.exclusive_minimum |= constraints.exclusive_minimum;
+        integer.exclusive_maximum |= constraints.exclusive_maximum;
+        integer.multiple_of = constraints.multiple_of.or(integer.multiple_of);
+    }
+}
+impl Number for f64 {
+    fn patch_type(ty: &mut Type, constraints: NumberConstraints<Self>) {
+        let number = match ty {
+            Type::Number(number) => number,
+            _ => panic!("number constraints on a schema which is not a number"),
+        };
+        number.minimum = constraints.minimum.or(number.minimum);
+        number.maximum = constraints.maximum.or(number.maximum);
+        number.exclusive_minimum |= constraints.exclusive_minimum;
+        number.exclusive_maximum |= constraints.exclusive_maximum;
+        number.multiple_of = constraints.multiple_of.or(number.multiple_of);
     }
 }
@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// An error while describing an app, returned by [`OpenapiApp::try_openapi`](crate::openapi_traits::OpenapiApp::try_openapi)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenapiError {
+    /// Multiple extractors of an operation describe the same media type of the request body with different schemas
+    ConflictingRequestBody { media_type: String },
+    /// Different schemas are registered under the same name in `components/schemas`,
+    /// e.g. two types with the same `ref_name`, or a type named `UserRequest` and the request view of a type `User`
+    ConflictingSchema { name: String },
+    /// The type of a `Query<T>` extractor is not described as an object, so its fields cannot be listed as query parameters
+    UnsupportedQuery { type_name: &'static str },
+    /// A route for a method filter which has no operation in a path item, like `any(handler)` or `connect(handler)`
+    UnsupportedMethodFilter { filter: &'static str },
+    /// An error in the operation of an HTTP method
+    Operation {
+        method: &'static str,
+        error: Box<OpenapiError>,
+    },
+    /// An error in the operations of a path
+    Path {
+        path: String,
+        error: Box<OpenapiError>,
+    },
+}
+
+impl fmt::Display for OpenapiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenapiError::ConflictingRequestBody { media_type } => write!(
+                f,
+                "conflicting schemas for the `{}` request body",
+                media_type
+            ),
+            OpenapiError::ConflictingSchema { name } => write!(
+                f,
+                "different schemas are registered as the component `{}`, consider renaming one of them using #[openapi(ref_name = \"...\")]",
+                name
+            ),
+            OpenapiError::UnsupportedQuery { type_name } => write!(
+                f,
+                "the query parameters `{}` are not described as an object",
+                type_name
+            ),
+            OpenapiError::UnsupportedMethodFilter { filter } => write!(
+                f,
+                "`{}` routes cannot be described, because there is no such operation in OpenAPI",
+                filter
+            ),
+            OpenapiError::Operation { method, error } => write!(f, "{}: {}", method, error),
+            OpenapiError::Path { path, error } => write!(f, "{} {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for OpenapiError {}
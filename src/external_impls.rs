@@ -0,0 +1,181 @@
+//! [`DescribeSchema`](crate::openapi_traits::DescribeSchema) implementations for types of other crates, each behind a cargo feature of the same name
+
+#[cfg(feature = "serde_json")]
+use openapiv3::*;
+
+#[cfg(feature = "serde_json")]
+use crate::openapi_traits::DescribeSchema;
+#[cfg(feature = "serde_json")]
+use crate::schema_generator::SchemaGenerator;
+#[cfg(feature = "serde_json")]
+use crate::utils;
+
+// `DateTime` serializes as RFC 3339, but `NaiveDateTime` has no offset, so it is not a `date-time`
+#[cfg(feature = "chrono")]
+impl_string! {
+    [Tz: chrono::TimeZone] chrono::DateTime<Tz> => "date-time";
+    [] chrono::NaiveDateTime;
+    [] chrono::NaiveDate => "date";
+    [] chrono::NaiveTime => "time";
+}
+
+// The `time` feature enables `time/serde-human-readable`, which serializes dates as `2021-02-03`,
+// but date-times as `2021-02-03 4:05:06.0 +00:00:00`, which is not RFC 3339. Fields using
+// `#[serde(with = "time::serde::rfc3339")]` can be documented as a `date-time` with `#[openapi(format = "date-time")]`.
+#[cfg(feature = "time")]
+impl_string! {
+    [] time::OffsetDateTime;
+    [] time::PrimitiveDateTime;
+    [] time::Date => "date";
+    [] time::Time => "time";
+}
+
+#[cfg(feature = "uuid")]
+impl_string! {
+    [] uuid::Uuid => "uuid";
+}
+
+#[cfg(feature = "url")]
+impl_string! {
+    [] url::Url => "uri";
+}
+
+#[cfg(feature = "rust_decimal")]
+impl_string! {
+    [] rust_decimal::Decimal => "decimal";
+}
+
+/// `Bytes` serialize as a sequence of `u8`s like a `Vec<u8>`, which is an array of integers in JSON
+#[cfg(feature = "bytes")]
+mod bytes_impls {
+    use openapiv3::Schema;
+
+    use crate::openapi_traits::{ArraySchema, DescribeSchema};
+    use crate::schema_generator::SchemaGenerator;
+
+    impl DescribeSchema for bytes::Bytes {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            Vec::<u8>::describe_schema(gen)
+        }
+    }
+    impl ArraySchema for bytes::Bytes {}
+
+    impl DescribeSchema for bytes::BytesMut {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            Vec::<u8>::describe_schema(gen)
+        }
+    }
+    impl ArraySchema for bytes::BytesMut {}
+}
+
+// networks serialize in CIDR notation like `10.0.0.0/8`, which has no `format`
+#[cfg(feature = "ipnetwork")]
+impl_string! {
+    [] ipnetwork::IpNetwork;
+    [] ipnetwork::Ipv4Network;
+    [] ipnetwork::Ipv6Network;
+}
+
+#[cfg(feature = "semver")]
+impl_string! {
+    [] semver::Version;
+    [] semver::VersionReq;
+}
+
+/// Any JSON value
+#[cfg(feature = "serde_json")]
+impl DescribeSchema for serde_json::Value {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        Schema {
+            schema_data: SchemaData::default(),
+            schema_kind: SchemaKind::Any(AnySchema::default()),
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl DescribeSchema for serde_json::Map<String, serde_json::Value> {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::ty_schema(Type::Object(ObjectType {
+            additional_properties: Some(AdditionalProperties::Any(true)),
+            ..Default::default()
+        }))
+    }
+}
+
+#[cfg(feature = "typed-header")]
+mod typed_headers {
+    use openapiv3::*;
+
+    use crate::openapi_traits::{DescribeHeader, DescribeSchema};
+    use crate::schema_generator::SchemaGenerator;
+    use crate::utils;
+
+    /// Headers whose values are described as a `string`, optionally with a `format` or `description`
+    macro_rules! impl_header {
+        ( $( [$($generics:tt)*] $ty:ty $(=> format: $format:literal)? $(, description: $description:literal)?; )* ) => {
+            $(
+                impl<$($generics)*> DescribeHeader for $ty {
+                    fn describe_header(_: &mut SchemaGenerator) -> Schema {
+                        #[allow(unused_mut)]
+                        let mut schema = utils::string_schema(None $(.or(Some($format)))?);
+                        $(schema.schema_data.description = Some($description.to_string());)?
+                        schema
+                    }
+                }
+            )*
+        };
+    }
+    impl_header! {
+        [] headers::AcceptRanges;
+        [] headers::AccessControlRequestHeaders;
+        [] headers::AccessControlRequestMethod;
+        [] headers::CacheControl;
+        [] headers::Connection;
+        [] headers::ContentEncoding;
+        [] headers::ContentType;
+        [] headers::Cookie;
+        [] headers::ETag;
+        [] headers::Host;
+        [] headers::IfMatch;
+        [] headers::IfNoneMatch;
+        [] headers::IfRange;
+        [] headers::Pragma;
+        [] headers::Range;
+        [] headers::Upgrade;
+        [] headers::UserAgent;
+        [] headers::Origin => format: "uri";
+        [] headers::Referer => format: "uri";
+        [] headers::Date, description: "An HTTP-date, like `Sun, 06 Nov 1994 08:49:37 GMT`";
+        [] headers::IfModifiedSince, description: "An HTTP-date, like `Sun, 06 Nov 1994 08:49:37 GMT`";
+        [] headers::IfUnmodifiedSince, description: "An HTTP-date, like `Sun, 06 Nov 1994 08:49:37 GMT`";
+    }
+
+    impl DescribeHeader for headers::ContentLength {
+        fn describe_header(gen: &mut SchemaGenerator) -> Schema {
+            u64::describe_schema(gen)
+        }
+    }
+
+    /// `{scheme} {credentials}`, like `Bearer <token>`, which is documented as the `http` security scheme `bearerAuth` or `basicAuth`
+    impl<C: headers::authorization::Credentials> DescribeHeader for headers::Authorization<C> {
+        fn describe_header(_: &mut SchemaGenerator) -> Schema {
+            utils::ty_schema(Type::String(StringType {
+                pattern: Some(format!("^{} ", C::SCHEME)),
+                ..Default::default()
+            }))
+        }
+
+        fn security_scheme() -> Option<(String, SecurityScheme)> {
+            let scheme = C::SCHEME.to_lowercase();
+            let name = format!("{}Auth", scheme);
+            Some((
+                name,
+                SecurityScheme::HTTP {
+                    scheme,
+                    bearer_format: None,
+                },
+            ))
+        }
+    }
+}
@@ -7,8 +7,8 @@ use openapiv3::*;
 pub async fn api_yaml() -> hyper::Response<hyper::Body> {
     utils::yaml_response(&*OPENAPI)
 }
-pub async fn api_json() -> axum::response::Json<openapiv3::OpenAPI> {
-    axum::response::Json(OPENAPI.clone())
+pub async fn api_json() -> hyper::Response<hyper::Body> {
+    utils::json_response(&*OPENAPI)
 }
 
 pub const OPENAPI: Lazy<openapiv3::OpenAPI> = Lazy::new(openapi);
@@ -1,15 +1,29 @@
 #![doc = include_str!("../README.md")]
 mod utils;
 
+// lets the derives, which refer to `axum_openapi::...`, be used in tests
+#[cfg(test)]
+extern crate self as axum_openapi;
+
+#[macro_use]
 mod describe_impl;
+mod error;
+mod external_impls;
 mod openapi_impl;
 mod operation_impl;
+mod schema_generator;
 
+pub mod multipart;
 pub mod openapi_adapters;
 pub mod openapi_traits;
 
-pub use axum_openapi_derive::DescribeSchema;
-pub use openapi_traits::DescribeSchema;
+pub use axum_openapi_derive::{DescribeMultipart, DescribeResponses, DescribeSchema};
+pub use error::OpenapiError;
+pub use openapi_traits::{
+    ArraySchema, DescribeHeader, DescribeMultipart, DescribeResponses, DescribeSchema,
+    DescribeSecurity, NumberSchema, SchemaView, StringSchema, UndocumentedParameter,
+};
+pub use schema_generator::SchemaGenerator;
 
 #[cfg(feature = "macro_based")]
 mod global_collect;
@@ -21,8 +35,11 @@ pub use axum_openapi_derive::routes;
 
 pub mod prelude {
     pub use crate::openapi_adapters::HandlerExt;
-    pub use crate::openapi_traits::{DescribeSchema, OpenapiApp};
-    pub use axum_openapi_derive::DescribeSchema;
+    pub use crate::openapi_traits::{
+        DescribeMultipart, DescribeResponses, DescribeSchema, OpenapiApp, SchemaView,
+    };
+    pub use crate::schema_generator::SchemaGenerator;
+    pub use axum_openapi_derive::{DescribeMultipart, DescribeResponses, DescribeSchema};
 }
 
 #[cfg(feature = "macro_based")]
@@ -35,6 +52,21 @@ use axum::{
 };
 use openapiv3::OpenAPI;
 
+/// Serializes an [OpenAPI] description as YAML.
+///
+/// `openapiv3` writes `bearerFormat: null` for `http` security schemes without a bearer format,
+/// like the `basicAuth` of a `TypedHeader<Authorization<Basic>>`, which is not valid OpenAPI.
+/// This function leaves it out, so descriptions should be written using it or [`to_json`] instead of using `serde` directly.
+pub fn to_yaml(api: &OpenAPI) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&utils::openapi_value(api)?)
+}
+
+/// Serializes an [OpenAPI] description as pretty-printed JSON, without the `bearerFormat: null` (see [`to_yaml`])
+pub fn to_json(api: &OpenAPI) -> Result<String, serde_json::Error> {
+    let value = utils::openapi_value(api).map_err(serde::ser::Error::custom)?;
+    serde_json::to_string_pretty(&value)
+}
+
 /// [axum] handler function responding with the provided [OpenAPI] yaml file
 pub fn openapi_yaml_endpoint<B: Send + Sync + 'static>(
     api: OpenAPI,
@@ -46,13 +78,20 @@ pub fn openapi_yaml_endpoint<B: Send + Sync + 'static>(
 pub fn openapi_json_endpoint<B: Send + Sync + 'static>(
     api: OpenAPI,
 ) -> OnMethod<IntoService<impl Handler<B, ()> + Clone, B, ()>, EmptyRouter> {
-    get(|| async { axum::response::Json(api) })
+    get(|| async move { utils::json_response(&api) })
 }
 
 #[doc(hidden)]
 pub mod __macro {
     pub use openapiv3;
 
+    pub use crate::utils::{
+        alias_properties, all_of_schema, append_description, constrain_array, constrain_format,
+        constrain_number, constrain_string, flatten_schema, inline_schema, multipart_media_type,
+        object_schema, one_of_schema, patch_schema_data, response, string_enum_schema,
+        string_format, tuple_schema, unit_schema,
+    };
+
     #[cfg(feature = "macro_based")]
     pub use inventory;
 
@@ -0,0 +1,89 @@
+//! Describing `multipart/form-data` request bodies, e.g. for file uploads
+
+use openapiv3::*;
+
+use crate::openapi_traits::DescribeSchema;
+use crate::schema_generator::SchemaGenerator;
+use crate::utils;
+
+/// A file in a [`DescribeMultipart`](crate::DescribeMultipart) form, described as a `string` with the `binary` format
+pub struct File;
+
+impl DescribeSchema for File {
+    fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+        utils::ty_schema(Type::String(StringType {
+            format: utils::string_format("binary"),
+            ..Default::default()
+        }))
+    }
+}
+
+#[cfg(feature = "multipart")]
+pub use extract::MultipartForm;
+
+#[cfg(feature = "multipart")]
+mod extract {
+    use std::future::Future;
+    use std::marker::PhantomData;
+    use std::ops::{Deref, DerefMut};
+    use std::pin::Pin;
+
+    use axum::extract::{FromRequest, Multipart, RequestParts};
+
+    /// A [`Multipart`] extractor whose parts are documented by `T: DescribeMultipart`
+    /// ```rust,no_run
+    /// use axum_openapi::multipart::{File, MultipartForm};
+    /// # use axum_openapi::prelude::*;
+    ///
+    /// #[derive(DescribeMultipart)]
+    /// struct Upload {
+    ///     picture: File,
+    /// }
+    ///
+    /// async fn upload(mut multipart: MultipartForm<Upload>) {
+    ///     while let Some(field) = multipart.next_field().await.unwrap() {
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    pub struct MultipartForm<T>(pub Multipart, PhantomData<fn() -> T>);
+
+    impl<T> MultipartForm<T> {
+        pub fn into_inner(self) -> Multipart {
+            self.0
+        }
+    }
+
+    impl<T> Deref for MultipartForm<T> {
+        type Target = Multipart;
+
+        fn deref(&self) -> &Multipart {
+            &self.0
+        }
+    }
+    impl<T> DerefMut for MultipartForm<T> {
+        fn deref_mut(&mut self) -> &mut Multipart {
+            &mut self.0
+        }
+    }
+
+    impl<T, B: Send> FromRequest<B> for MultipartForm<T>
+    where
+        Multipart: FromRequest<B>,
+    {
+        type Rejection = <Multipart as FromRequest<B>>::Rejection;
+
+        fn from_request<'req, 'fut>(
+            req: &'req mut RequestParts<B>,
+        ) -> Pin<Box<dyn Future<Output = Result<Self, Self::Rejection>> + Send + 'fut>>
+        where
+            'req: 'fut,
+            Self: 'fut,
+        {
+            Box::pin(async move {
+                let multipart = Multipart::from_request(req).await?;
+                Ok(MultipartForm(multipart, PhantomData))
+            })
+        }
+    }
+}
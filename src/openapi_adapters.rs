@@ -8,7 +8,9 @@ use axum::body::BoxBody;
 use axum::prelude::*;
 use hyper::Request;
 
+use crate::error::OpenapiError;
 use crate::openapi_traits::OperationHandler;
+use crate::utils;
 
 type BodyFuture<'a> = Pin<Box<dyn Future<Output = hyper::Response<BoxBody>> + Send + 'a>>;
 
@@ -17,6 +19,15 @@ pub trait HandlerExt<B, In>: Handler<B, In> {
     fn with_openapi<F>(self, supplier: F) -> WithOpenapiHandler<Self, B, In, F>
     where
         F: Fn() -> Operation + Clone;
+    /// Documents the `default` response, e.g. of a handler returning `(StatusCode, Json<T>)`, as a response with the given status code.
+    ///
+    /// Without a `default` response the `200` response is moved, e.g. `.with_status(201)` for a handler returning `Json<T>`.
+    /// If there is neither, a response without a body is documented.
+    ///
+    /// Can be called multiple times if the handler responds with different status codes:
+    /// `add_pet.with_status(200).with_status(201)`.
+    /// Redirects like `(StatusCode, HeaderMap, ())` with a `3xx` status code document the `Location` header.
+    fn with_status(self, status: u16) -> WithStatusHandler<Self, B, In>;
 }
 impl<H: Handler<B, In>, B, In> HandlerExt<B, In> for H {
     fn ignore_openapi(self) -> IgnoreOpenapiHandler<Self, B, In> {
@@ -29,6 +40,10 @@ impl<H: Handler<B, In>, B, In> HandlerExt<B, In> for H {
     {
         WithOpenapiHandler::new(self, supplier)
     }
+
+    fn with_status(self, status: u16) -> WithStatusHandler<Self, B, In> {
+        WithStatusHandler::new(self, status)
+    }
 }
 
 pub struct IgnoreOpenapiHandler<H: Handler<B, In>, B, In>(H, PhantomData<fn() -> (B, In)>);
@@ -57,7 +72,9 @@ impl<H: Handler<B, In> + Sized, B, In> Handler<B, In> for IgnoreOpenapiHandler<H
     }
 }
 impl<H: Handler<B, In>, B, In> OperationHandler<()> for IgnoreOpenapiHandler<H, B, In> {
-    fn modify_op(&self, _: &mut OpenAPI, _: &mut Operation) {}
+    fn modify_op(&self, _: &mut OpenAPI, _: &mut Operation) -> Result<(), OpenapiError> {
+        Ok(())
+    }
 }
 
 pub struct WithOpenapiHandler<H, B, In, F>(H, F, PhantomData<fn() -> (B, In)>)
@@ -103,7 +120,97 @@ impl<H: Handler<B, In>, B, In, F> OperationHandler<()> for WithOpenapiHandler<H,
 where
     F: Fn() -> Operation + Clone,
 {
-    fn modify_op(&self, _: &mut OpenAPI, op: &mut Operation) {
+    fn modify_op(&self, _: &mut OpenAPI, op: &mut Operation) -> Result<(), OpenapiError> {
         *op = (self.1)();
+        Ok(())
+    }
+}
+
+pub struct WithStatusHandler<H: Handler<B, In>, B, In>(H, Vec<u16>, PhantomData<fn() -> (B, In)>);
+impl<H: Handler<B, In>, B, In> WithStatusHandler<H, B, In> {
+    pub fn new(handler: H, status: u16) -> Self {
+        Self(handler, vec![status], PhantomData)
+    }
+    pub fn service(&self) -> &H {
+        &self.0
+    }
+    /// Adds another status code for the `default` response
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.1.push(status);
+        self
+    }
+}
+
+impl<H: Handler<B, In> + Clone, B, In> Clone for WithStatusHandler<H, B, In> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), self.1.clone(), PhantomData)
+    }
+}
+impl<H: Handler<B, In> + Sized, B, In> Handler<B, In> for WithStatusHandler<H, B, In> {
+    type Sealed = axum::handler::sealed::Hidden;
+
+    fn call<'a>(self, req: Request<B>) -> BodyFuture<'a>
+    where
+        Self: 'a,
+    {
+        self.0.call(req)
+    }
+}
+impl<H, B, In, Params> OperationHandler<Params> for WithStatusHandler<H, B, In>
+where
+    H: Handler<B, In> + OperationHandler<Params>,
+{
+    fn modify_op(&self, openapi: &mut OpenAPI, op: &mut Operation) -> Result<(), OpenapiError> {
+        self.0.modify_op(openapi, op)?;
+
+        let success = match op.responses.default.take() {
+            Some(ReferenceOr::Item(default)) => Some(default),
+            default => {
+                op.responses.default = default;
+                let ok = StatusCode::Code(200);
+                match op.responses.responses.shift_remove(&ok) {
+                    Some(ReferenceOr::Item(response)) => Some(response),
+                    Some(reference) => {
+                        op.responses.responses.insert(ok, reference);
+                        None
+                    }
+                    None => None,
+                }
+            }
+        };
+        for &status in &self.1 {
+            let (status, mut response) = utils::response(status, None, None);
+            if let Some(success) = &success {
+                response.headers.extend(success.headers.clone());
+                response.content = success.content.clone();
+            }
+            utils::insert_response(&mut op.responses, (status, response));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn add_pet() -> axum::response::Json<u32> {
+        axum::response::Json(0)
+    }
+
+    #[test]
+    fn with_status_moves_the_ok_response() {
+        let handler = HandlerExt::<hyper::Body, _>::with_status(add_pet, 201);
+        let mut op = Operation::default();
+        handler.modify_op(&mut OpenAPI::default(), &mut op).unwrap();
+
+        let statuses: Vec<_> = op.responses.responses.keys().collect();
+        assert_eq!(statuses, vec![&StatusCode::Code(201)]);
+        let created = match &op.responses.responses[&StatusCode::Code(201)] {
+            ReferenceOr::Item(response) => response,
+            _ => panic!("expected a response"),
+        };
+        assert_eq!(created.description, "Created");
+        assert!(created.content.contains_key("application/json"));
     }
 }
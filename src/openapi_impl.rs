@@ -1,8 +1,10 @@
 use std::borrow::Cow;
 
+use crate::error::OpenapiError;
 use crate::openapi_traits::{
     OpenapiApp, OperationAtPath, OperationHandler, OperationParameter, OperationResult,
 };
+use crate::operation_impl;
 use openapiv3::*;
 
 use axum::handler::IntoService;
@@ -20,8 +22,9 @@ macro_rules! impl_function_operation {
             Ret: OperationResult
         {
             #[allow(unused)]
-            fn modify_op(&self, openapi: &mut OpenAPI, op: &mut Operation) {
-                 $(<$param as OperationParameter>::modify_op(openapi, op, true);)*
+            fn modify_op(&self, openapi: &mut OpenAPI, op: &mut Operation) -> Result<(), OpenapiError> {
+                 $(<$param as OperationParameter>::modify_op(openapi, op, true)?;)*
+                 <Ret as OperationResult>::modify_op(openapi, op)
             }
         }
     };
@@ -34,55 +37,44 @@ where
     H: OperationHandler<Params>,
     Fallback: OperationAtPath<FallbackParams>,
 {
-    fn modify_path_item(&self, openapi: &mut OpenAPI, path_item: &mut PathItem) {
-        Fallback::modify_path_item(&self.fallback, openapi, path_item);
-        match self.method {
-            MethodFilter::Get => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.get.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Post => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.post.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Patch => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.patch.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Delete => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.delete.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Head => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.head.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Options => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.options.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Put => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.put.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Trace => H::modify_op(
-                &self.svc.handler,
-                openapi,
-                path_item.trace.get_or_insert_with(Default::default),
-            ),
-            MethodFilter::Any | MethodFilter::Connect => todo!(),
-        }
+    fn modify_path_item(
+        &self,
+        openapi: &mut OpenAPI,
+        path_item: &mut PathItem,
+    ) -> Result<(), OpenapiError> {
+        Fallback::modify_path_item(&self.fallback, openapi, path_item)?;
+        let (method, operation) = match self.method {
+            MethodFilter::Get => ("GET", &mut path_item.get),
+            MethodFilter::Post => ("POST", &mut path_item.post),
+            MethodFilter::Patch => ("PATCH", &mut path_item.patch),
+            MethodFilter::Delete => ("DELETE", &mut path_item.delete),
+            MethodFilter::Head => ("HEAD", &mut path_item.head),
+            MethodFilter::Options => ("OPTIONS", &mut path_item.options),
+            MethodFilter::Put => ("PUT", &mut path_item.put),
+            MethodFilter::Trace => ("TRACE", &mut path_item.trace),
+            MethodFilter::Any => {
+                return Err(OpenapiError::UnsupportedMethodFilter { filter: "any" })
+            }
+            MethodFilter::Connect => {
+                return Err(OpenapiError::UnsupportedMethodFilter { filter: "connect" })
+            }
+        };
+        let operation = operation.get_or_insert_with(Default::default);
+        H::modify_op(&self.svc.handler, openapi, operation)
+            .and_then(|()| match self.method {
+                MethodFilter::Get => operation_impl::form_as_query(openapi, operation),
+                _ => Ok(()),
+            })
+            .map_err(|error| OpenapiError::Operation {
+                method,
+                error: Box::new(error),
+            })
     }
 }
 impl OperationAtPath<()> for EmptyRouter {
-    fn modify_path_item(&self, _: &mut OpenAPI, _: &mut PathItem) {}
+    fn modify_path_item(&self, _: &mut OpenAPI, _: &mut PathItem) -> Result<(), OpenapiError> {
+        Ok(())
+    }
 }
 
 impl<Service, Fallback, ServiceParams, FallbackParams> OpenapiApp<(ServiceParams, FallbackParams)>
@@ -91,16 +83,24 @@ where
     Service: OperationAtPath<ServiceParams>,
     Fallback: OpenapiApp<FallbackParams>,
 {
-    fn modify_openapi(&self, api: &mut OpenAPI) {
+    fn modify_openapi(&self, api: &mut OpenAPI) -> Result<(), OpenapiError> {
         let mut path_item = PathItem::default();
-        OpenapiApp::modify_openapi(&self.fallback, api);
-        OperationAtPath::modify_path_item(&self.svc, api, &mut path_item);
+        OpenapiApp::modify_openapi(&self.fallback, api)?;
         let path = axum_path_to_openapi(&self.path);
+        OperationAtPath::modify_path_item(&self.svc, api, &mut path_item).map_err(|error| {
+            OpenapiError::Path {
+                path: path.clone(),
+                error: Box::new(error),
+            }
+        })?;
         api.paths.insert(path, ReferenceOr::Item(path_item));
+        Ok(())
     }
 }
 impl OpenapiApp<()> for EmptyRouter {
-    fn modify_openapi(&self, _: &mut OpenAPI) {}
+    fn modify_openapi(&self, _: &mut OpenAPI) -> Result<(), OpenapiError> {
+        Ok(())
+    }
 }
 
 fn axum_path_to_openapi(path: &str) -> String {
@@ -123,6 +123,11 @@ fn axum_path_to_openapi(path: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::axum_path_to_openapi;
+    use crate::error::OpenapiError;
+    use crate::openapi_traits::{DescribeSchema, OpenapiApp};
+    use crate::schema_generator::SchemaGenerator;
+    use crate::utils;
+    use openapiv3::*;
 
     #[test]
     fn axum_path() {
@@ -131,4 +136,127 @@ mod tests {
         assert_eq!(axum_path_to_openapi("/pets/:id/"), "/pets/{id}/");
         assert_eq!(axum_path_to_openapi("pets/:id/"), "pets/{id}/");
     }
+
+    #[test]
+    fn any_routes_are_an_error() {
+        async fn handler() {}
+        let app = axum::route("/pets", axum::handler::any::<_, hyper::Body, _>(handler));
+        assert_eq!(
+            app.try_openapi(),
+            Err(OpenapiError::Path {
+                path: "/pets".to_string(),
+                error: Box::new(OpenapiError::UnsupportedMethodFilter { filter: "any" }),
+            })
+        );
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Search {
+        #[allow(dead_code)]
+        query: String,
+    }
+    impl DescribeSchema for Search {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            utils::object_schema(
+                vec![("query".to_string(), gen.subschema_for::<String>())],
+                vec!["query".to_string()],
+            )
+        }
+    }
+
+    #[test]
+    fn forms_of_get_requests_are_query_parameters() {
+        async fn handler(_: axum::extract::Form<Search>) {}
+        let app = axum::route(
+            "/pets",
+            axum::handler::get::<_, hyper::Body, _>(handler).post::<_, _, _>(handler),
+        );
+        let openapi = app.try_openapi().unwrap();
+        let path_item = match &openapi.paths["/pets"] {
+            ReferenceOr::Item(path_item) => path_item,
+            _ => panic!("expected a path item"),
+        };
+
+        let get = path_item.get.as_ref().unwrap();
+        assert_eq!(get.request_body, None);
+        let names: Vec<_> = get
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                ReferenceOr::Item(Parameter::Query { parameter_data, .. }) => {
+                    parameter_data.name.as_str()
+                }
+                _ => panic!("expected a query parameter"),
+            })
+            .collect();
+        assert_eq!(names, vec!["query"]);
+
+        let post = path_item.post.as_ref().unwrap();
+        assert!(post.parameters.is_empty());
+        assert!(post.request_body.is_some());
+    }
+
+    #[test]
+    fn result_errors_are_responses() {
+        use axum::response::Json;
+        use hyper::StatusCode as Status;
+
+        async fn status() -> Result<Json<String>, Status> {
+            Err(Status::NOT_FOUND)
+        }
+        async fn message() -> Result<Json<String>, (Status, &'static str)> {
+            Err((Status::BAD_REQUEST, "invalid"))
+        }
+        let app = axum::route(
+            "/pets",
+            axum::handler::get::<_, hyper::Body, _>(status).post::<_, _, _>(message),
+        );
+        let openapi = app.try_openapi().unwrap();
+        let path_item = match &openapi.paths["/pets"] {
+            ReferenceOr::Item(path_item) => path_item,
+            _ => panic!("expected a path item"),
+        };
+        let responses = |op: &Option<Operation>| {
+            let responses = op.as_ref().unwrap().responses.clone();
+            let default = match responses.default {
+                Some(ReferenceOr::Item(default)) => default,
+                _ => panic!("expected a default response"),
+            };
+            let ok: Vec<_> = responses.responses.keys().cloned().collect();
+            (ok, default)
+        };
+
+        let (ok, default) = responses(&path_item.get);
+        assert_eq!(ok, vec![StatusCode::Code(200)]);
+        assert_eq!(default.description, "Error response");
+        assert!(default.content.is_empty());
+
+        let (ok, default) = responses(&path_item.post);
+        assert_eq!(ok, vec![StatusCode::Code(200)]);
+        assert_eq!(
+            default.content.keys().collect::<Vec<_>>(),
+            vec!["text/plain"]
+        );
+    }
+
+    #[cfg(feature = "typed-header")]
+    #[test]
+    fn serialized_basic_auth_has_no_bearer_format() {
+        use axum::extract::TypedHeader;
+        use headers::authorization::{Authorization, Basic};
+
+        async fn handler(_: TypedHeader<Authorization<Basic>>) {}
+        let app = axum::route("/pets", axum::handler::get::<_, hyper::Body, _>(handler));
+        let openapi = app.try_openapi().unwrap();
+
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(&crate::to_yaml(&openapi).unwrap()).unwrap();
+        let json: serde_yaml::Value =
+            serde_yaml::from_str(&crate::to_json(&openapi).unwrap()).unwrap();
+        for value in [yaml, json] {
+            let scheme = &value["components"]["securitySchemes"]["basicAuth"];
+            assert_eq!(scheme["scheme"], serde_yaml::Value::from("basic"));
+            assert!(scheme.get("bearerFormat").is_none());
+        }
+    }
 }
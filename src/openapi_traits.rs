@@ -1,21 +1,241 @@
 use openapiv3::*;
 
+use crate::error::OpenapiError;
+use crate::schema_generator::SchemaGenerator;
+use crate::utils;
+
+/// Which direction of an API a schema describes.
+///
+/// A type can look different when it is serialized than when it is deserialized,
+/// e.g. fields with `#[serde(skip_serializing_if = "...")]` may be missing in responses but are still required in requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaView {
+    /// Data sent by the client and deserialized by the server
+    Request,
+    /// Data serialized by the server and sent to the client
+    Response,
+}
+
 /// Trait which describes a rust type as an [`openapiv3::Schema`]
 pub trait DescribeSchema {
-    fn describe_schema() -> Schema;
+    /// Nested types should be described using [`SchemaGenerator::subschema_for`], so that named types are referenced.
+    fn describe_schema(gen: &mut SchemaGenerator) -> Schema;
 
     /// If this returns a string, then the schema will be written to the `components/schemas` section instead of inlined at its use.
     fn ref_name() -> Option<String> {
         None
     }
 
-    fn reference_or_schema() -> ReferenceOr<Schema> {
-        match Self::ref_name() {
-            Some(ref_name) => ReferenceOr::Reference {
-                reference: format!("#/components/schemas/{}", ref_name),
-            },
-            None => ReferenceOr::Item(Self::describe_schema()),
-        }
+    /// The name of this type as a generic argument of a named type, e.g. the `Pet` in `Page_Pet`.
+    ///
+    /// Defaults to the [`ref_name`](DescribeSchema::ref_name), or the rust type name without module paths.
+    /// ```rust
+    /// # use axum_openapi::prelude::*;
+    /// #[derive(DescribeSchema)]
+    /// struct Pet {
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(DescribeSchema)]
+    /// #[openapi(ref_name = "{T}Page")]
+    /// struct Page<T> {
+    ///     items: Vec<T>,
+    /// }
+    ///
+    /// assert_eq!(Page::<Pet>::ref_name().unwrap(), "PetPage");
+    /// assert_eq!(Page::<Vec<Pet>>::ref_name().unwrap(), "Vec_PetPage");
+    /// ```
+    fn type_name() -> String {
+        Self::ref_name().unwrap_or_else(|| utils::type_name::<Self>())
+    }
+
+    /// Whether a field of this type may be missing from an object, like an [`Option`].
+    fn is_optional() -> bool {
+        false
+    }
+}
+
+/// Types described as a `string`, which accept the `min_length`, `max_length`, `pattern` and `format` constraints of `#[openapi(...)]`
+///
+/// String constraints on other types are a compile error:
+/// ```compile_fail
+/// # use axum_openapi::prelude::*;
+/// #[derive(DescribeSchema)]
+/// struct Pet {
+///     #[openapi(pattern = "^[0-9]+$")]
+///     id: u32,
+/// }
+/// ```
+pub trait StringSchema: DescribeSchema {}
+
+/// Types described as an `integer` or `number`, which accept the `minimum`, `maximum`, `exclusive_minimum`, `exclusive_maximum`, `multiple_of` and `format` constraints of `#[openapi(...)]`
+pub trait NumberSchema: DescribeSchema {
+    /// `i64` for integers and `f64` for numbers, the type of the bounds in the schema
+    type Number: Number;
+}
+
+/// The type of the bounds of a [`NumberSchema`]
+pub trait Number: Copy + Default {
+    fn patch_type(ty: &mut Type, constraints: NumberConstraints<Self>);
+}
+
+/// The bounds set by `#[openapi(...)]` on a [`NumberSchema`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NumberConstraints<N> {
+    pub minimum: Option<N>,
+    pub maximum: Option<N>,
+    pub exclusive_minimum: bool,
+    pub exclusive_maximum: bool,
+    pub multiple_of: Option<N>,
+}
+
+impl<N> NumberConstraints<N> {
+    /// Converts the literal of a bound to `N`. Integer literals can be used for both integers and numbers, but float literals only for numbers.
+    pub fn bound<B: IntoBound<N>>(&self, bound: B) -> Option<N> {
+        Some(bound.into_bound())
+    }
+}
+
+/// Conversion of `i64` and `f64` literals to the [`Number`] of a [`NumberSchema`]
+pub trait IntoBound<N> {
+    fn into_bound(self) -> N;
+}
+impl IntoBound<i64> for i64 {
+    fn into_bound(self) -> i64 {
+        self
+    }
+}
+impl IntoBound<f64> for i64 {
+    fn into_bound(self) -> f64 {
+        self as f64
+    }
+}
+impl IntoBound<f64> for f64 {
+    fn into_bound(self) -> f64 {
+        self
+    }
+}
+
+/// Types described as an `array`, which accept the `min_items`, `max_items` and `unique_items` constraints of `#[openapi(...)]`
+pub trait ArraySchema: DescribeSchema {}
+
+/// Describes the responses of an error type, like the `E` of a handler returning `Result<Json<T>, E>`.
+///
+/// Errors like `StatusCode` or `(StatusCode, String)`, whose status code is only known at runtime, are the `default` response.
+///
+/// Can be derived for enums, where every variant is a response with a `#[openapi(status = 404)]` and an optional body:
+/// ```rust
+/// # use axum_openapi::prelude::*;
+/// # #[derive(DescribeSchema)]
+/// # struct ErrorBody { message: String }
+/// #[derive(DescribeResponses)]
+/// enum ApiError {
+///     /// The pet does not exist
+///     #[openapi(status = 404)]
+///     NotFound,
+///     #[openapi(status = 400)]
+///     Invalid(ErrorBody),
+/// }
+/// ```
+pub trait DescribeResponses {
+    /// The status codes and responses, with bodies described using [`SchemaGenerator::subschema_for`]
+    fn describe_responses(gen: &mut SchemaGenerator) -> Vec<(StatusCode, Response)>;
+
+    /// The `default` response of errors whose status code is only known at runtime, like a [`hyper::StatusCode`]
+    fn describe_default_response(_: &mut SchemaGenerator) -> Option<Response> {
+        None
+    }
+}
+
+/// Describes the parts of a `multipart/form-data` request body, which are documented for handlers taking a `multipart::MultipartForm<T>` (with the `multipart` feature).
+///
+/// Can be derived for structs, where every field is a part. Files are described using [`File`](crate::multipart::File),
+/// and the content types of a part can be set with `#[openapi(content_type = "...")]`:
+/// ```rust
+/// use axum_openapi::multipart::File;
+/// # use axum_openapi::prelude::*;
+///
+/// #[derive(DescribeMultipart)]
+/// struct Upload {
+///     /// The name of the pet
+///     name: String,
+///     #[openapi(content_type = "image/png, image/jpeg")]
+///     picture: File,
+///     attachments: Option<Vec<File>>,
+/// }
+/// ```
+pub trait DescribeMultipart {
+    /// An object schema with a property for each part, and the `encoding` of the parts
+    fn describe_multipart(gen: &mut SchemaGenerator) -> MediaType;
+}
+
+/// Describes the value of a header, which is documented for handlers taking a `TypedHeader<H>` (with the `typed-header` feature).
+///
+/// The name of the header parameter is taken from `headers::Header::name`. `Accept` and `Content-Type` are not documented,
+/// because OpenAPI ignores header parameters with these names. Custom headers are described as a `string` by default:
+/// ```rust
+/// # use axum_openapi::DescribeHeader;
+/// struct RequestId(String);
+///
+/// impl DescribeHeader for RequestId {}
+/// ```
+pub trait DescribeHeader {
+    fn describe_header(gen: &mut SchemaGenerator) -> Schema {
+        String::describe_schema(gen)
+    }
+
+    /// Headers which authenticate a request, like `Authorization<Bearer>`, return their [`DescribeSecurity::security_scheme`].
+    /// They are documented as a security requirement instead of a header parameter.
+    fn security_scheme() -> Option<(String, SecurityScheme)> {
+        None
+    }
+}
+
+/// Describes how an extractor authenticates a request.
+///
+/// Custom authentication extractors can add themselves as a security requirement in their [`OperationParameter`] implementation:
+/// ```rust
+/// use axum_openapi::{DescribeSecurity, OpenapiError};
+/// use axum_openapi::openapi_traits::OperationParameter;
+/// use openapiv3::{APIKeyLocation, OpenAPI, Operation, SecurityScheme};
+///
+/// struct ApiKey(String);
+///
+/// impl DescribeSecurity for ApiKey {
+///     fn security_scheme() -> (String, SecurityScheme) {
+///         let scheme = SecurityScheme::APIKey {
+///             location: APIKeyLocation::Header,
+///             name: "X-API-Key".to_string(),
+///         };
+///         ("apiKey".to_string(), scheme)
+///     }
+/// }
+///
+/// impl OperationParameter for ApiKey {
+///     fn modify_op(openapi: &mut OpenAPI, op: &mut Operation, required: bool) -> Result<(), OpenapiError> {
+///         Self::add_security_requirement(openapi, op, required);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait DescribeSecurity {
+    /// The scheme and its name in `components/securitySchemes`
+    fn security_scheme() -> (String, SecurityScheme);
+
+    /// The scopes which are required for OAuth2 and OpenID Connect
+    fn scopes() -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Adds the scheme to `components/securitySchemes` and requires it for the operation
+    fn add_security_requirement(openapi: &mut OpenAPI, operation: &mut Operation, required: bool) {
+        utils::add_security_requirement(
+            openapi,
+            operation,
+            Self::security_scheme(),
+            Self::scopes(),
+            required,
+        );
     }
 }
 
@@ -30,38 +250,68 @@ pub trait DescribeSchema {
 ///     .route("/", get(handler));
 /// # hyper::server::Server::bind(todo!()).serve(app.into_make_service());
 ///
-/// println!("{:?}", app.openapi());
+/// println!("{}", axum_openapi::to_yaml(&app.openapi()).unwrap());
 /// ```
 pub trait OpenapiApp<Params> {
-    fn modify_openapi(&self, api: &mut OpenAPI);
+    fn modify_openapi(&self, api: &mut OpenAPI) -> Result<(), OpenapiError>;
 
+    /// Like [`try_openapi`](OpenapiApp::try_openapi), but panics if the app cannot be described
     fn openapi(&self) -> OpenAPI {
+        self.try_openapi()
+            .unwrap_or_else(|error| panic!("failed to describe the app: {}", error))
+    }
+
+    /// Describes the app.
+    ///
+    /// The description should be serialized using [`to_yaml`](crate::to_yaml) or [`to_json`](crate::to_json),
+    /// because `openapiv3` writes an invalid `bearerFormat: null` for some security schemes.
+    fn try_openapi(&self) -> Result<OpenAPI, OpenapiError> {
         let mut openapi = OpenAPI::default();
-        self.modify_openapi(&mut openapi);
+        self.modify_openapi(&mut openapi)?;
 
         fix_path_params(&mut openapi);
 
-        openapi
+        Ok(openapi)
     }
 }
 
 /// Implemented for [`axum::handler::get/post/...`](axum::handler)
 pub trait OperationAtPath<Params> {
-    fn modify_path_item(&self, openapi: &mut OpenAPI, path_item: &mut PathItem);
+    fn modify_path_item(
+        &self,
+        openapi: &mut OpenAPI,
+        path_item: &mut PathItem,
+    ) -> Result<(), OpenapiError>;
 }
 
 /// Describes an [`axum::handler::Handler`] as a [`openapiv3::Operation`]
 pub trait OperationHandler<Params> {
-    fn modify_op(&self, openapi: &mut OpenAPI, operation: &mut Operation);
+    fn modify_op(
+        &self,
+        openapi: &mut OpenAPI,
+        operation: &mut Operation,
+    ) -> Result<(), OpenapiError>;
 }
 
 /// Implemeted for most types in [`axum::extract`], i.e. parameters to handler functions.
 pub trait OperationParameter {
-    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation, required: bool);
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        operation: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError>;
 }
+/// Extractors which are not visible in the API, like shared state or a database pool, and are skipped when describing a handler.
+/// ```rust
+/// struct DbPool;
+///
+/// impl axum_openapi::UndocumentedParameter for DbPool {}
+/// ```
+pub trait UndocumentedParameter {}
+
 /// Describes the return value of a handler function for an [`openapiv3::Operation`]
 pub trait OperationResult {
-    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation);
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError>;
 }
 
 fn fix_path_params(openapi: &mut OpenAPI) {
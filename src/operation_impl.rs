@@ -1,44 +1,176 @@
 use axum_openapi_derive::all_tuples;
 use openapiv3::*;
 
-use crate::openapi_traits::{DescribeSchema, OperationParameter, OperationResult};
+use crate::error::OpenapiError;
+#[cfg(feature = "typed-header")]
+use crate::openapi_traits::DescribeHeader;
+#[cfg(feature = "multipart")]
+use crate::openapi_traits::DescribeMultipart;
+use crate::openapi_traits::{
+    DescribeResponses, DescribeSchema, OperationParameter, OperationResult, SchemaView,
+    UndocumentedParameter,
+};
+use crate::schema_generator::SchemaGenerator;
+use crate::utils;
 
 impl<T: OperationParameter> OperationParameter for Option<T> {
-    fn modify_op(openapi: &mut OpenAPI, op: &mut Operation, _: bool) {
-        T::modify_op(openapi, op, false);
+    fn modify_op(openapi: &mut OpenAPI, op: &mut Operation, _: bool) -> Result<(), OpenapiError> {
+        T::modify_op(openapi, op, false)
     }
 }
 
+impl<T: UndocumentedParameter> OperationParameter for T {
+    fn modify_op(_: &mut OpenAPI, _: &mut Operation, _: bool) -> Result<(), OpenapiError> {
+        Ok(())
+    }
+}
+
+impl<T> UndocumentedParameter for axum::extract::Extension<T> {}
+impl<T> UndocumentedParameter for axum::extract::ConnectInfo<T> {}
+impl<B> UndocumentedParameter for hyper::Request<B> {}
+impl UndocumentedParameter for hyper::Method {}
+impl UndocumentedParameter for hyper::Uri {}
+impl UndocumentedParameter for hyper::Version {}
+impl UndocumentedParameter for hyper::HeaderMap {}
+
 impl<T: DescribeSchema> OperationParameter for axum::extract::Json<T> {
-    fn modify_op(openapi: &mut OpenAPI, op: &mut Operation, required: bool) {
-        if op.request_body.is_some() {
-            todo!();
-        }
-
-        if let Some(ref_name) = T::ref_name() {
-            openapi
-                .components
-                .get_or_insert_with(Default::default)
-                .schemas
-                .insert(ref_name, ReferenceOr::Item(T::describe_schema()));
-        }
-
-        op.request_body = Some(ReferenceOr::Item(RequestBody {
-            description: None,
-            content: std::array::IntoIter::new([(
-                "application/json".to_string(),
-                MediaType {
-                    schema: Some(T::reference_or_schema()),
-                    example: None,
-                    examples: Default::default(),
-                    encoding: Default::default(),
-                },
-            )])
-            .collect(),
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        op: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError> {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        let schema = gen.subschema_for::<T>();
+        gen.register_components(openapi)?;
+
+        request_body(op, utils::json_media_type(schema), required)
+    }
+}
+
+const FORM_MEDIA_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Documented as a request body. For `GET` requests axum reads the form from the query string instead,
+/// so the body is turned into query parameters by [`form_as_query`] once the method is known.
+impl<T: DescribeSchema> OperationParameter for axum::extract::Form<T> {
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        op: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError> {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        let schema = gen.subschema_for::<T>();
+        gen.register_components(openapi)?;
+
+        request_body(op, utils::media_type(FORM_MEDIA_TYPE, schema), required)
+    }
+}
+
+/// Moves the fields of a `Form` request body to the query parameters, where axum reads them for `GET` requests
+pub(crate) fn form_as_query(openapi: &OpenAPI, op: &mut Operation) -> Result<(), OpenapiError> {
+    let request_body = match &mut op.request_body {
+        Some(ReferenceOr::Item(request_body)) => request_body,
+        _ => return Ok(()),
+    };
+    let schema = match request_body.content.shift_remove(FORM_MEDIA_TYPE) {
+        Some(MediaType {
+            schema: Some(schema),
+            ..
+        }) => schema,
+        _ => return Ok(()),
+    };
+    let required = request_body.required;
+    if request_body.content.is_empty() {
+        op.request_body = None;
+    }
+
+    query_parameters(openapi, op, schema, required).ok_or(OpenapiError::UnsupportedQuery {
+        type_name: "axum::extract::Form",
+    })
+}
+
+macro_rules! impl_content_parameter {
+    ( $( [$($generics:tt)*] $ty:ty => $media_type:literal $(, format: $format:literal)?; )* ) => {
+        $(
+            impl<$($generics)*> OperationParameter for $ty {
+                fn modify_op(_: &mut OpenAPI, op: &mut Operation, required: bool) -> Result<(), OpenapiError> {
+                    let schema = ReferenceOr::Item(utils::string_schema(None $(.or(Some($format)))?));
+                    request_body(op, utils::media_type($media_type, schema), required)
+                }
+            }
+        )*
+    };
+}
+impl_content_parameter! {
+    [] String => "text/plain";
+    [] hyper::body::Bytes => "application/octet-stream", format: "binary";
+    [B] axum::extract::Body<B> => "application/octet-stream", format: "binary";
+    [B] axum::extract::BodyStream<B> => "application/octet-stream", format: "binary";
+}
+
+#[cfg(feature = "multipart")]
+impl OperationParameter for axum::extract::Multipart {
+    fn modify_op(_: &mut OpenAPI, op: &mut Operation, required: bool) -> Result<(), OpenapiError> {
+        let schema = ReferenceOr::Item(utils::object_schema(Vec::new(), Vec::new()));
+        request_body(
+            op,
+            utils::media_type("multipart/form-data", schema),
             required,
-            extensions: Default::default(),
-        }));
+        )
+    }
+}
+
+#[cfg(feature = "multipart")]
+impl<T: DescribeMultipart> OperationParameter for crate::multipart::MultipartForm<T> {
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        op: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError> {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        let media_type = T::describe_multipart(&mut gen);
+        gen.register_components(openapi)?;
+
+        request_body(
+            op,
+            ("multipart/form-data".to_string(), media_type),
+            required,
+        )
+    }
+}
+
+/// Adds a media type to the request body of `op`.
+///
+/// Handlers with multiple body extractors accept all of their media types, but the same media type cannot be described differently.
+fn request_body(
+    op: &mut Operation,
+    (name, media_type): (String, MediaType),
+    required: bool,
+) -> Result<(), OpenapiError> {
+    let request_body = match &mut op.request_body {
+        Some(ReferenceOr::Item(request_body)) => request_body,
+        Some(ReferenceOr::Reference { .. }) => return Ok(()),
+        None => {
+            op.request_body = Some(ReferenceOr::Item(RequestBody {
+                description: None,
+                content: std::iter::once((name, media_type)).collect(),
+                required,
+                extensions: Default::default(),
+            }));
+            return Ok(());
+        }
+    };
+
+    match request_body.content.get(&name) {
+        Some(existing) if *existing != media_type => {
+            return Err(OpenapiError::ConflictingRequestBody { media_type: name })
+        }
+        Some(_) => {}
+        None => {
+            request_body.content.insert(name, media_type);
+        }
     }
+    request_body.required |= required;
+    Ok(())
 }
 
 macro_rules! impl_url_params {
@@ -46,9 +178,12 @@ macro_rules! impl_url_params {
 
         #[allow(deprecated)]
         impl<$($param: DescribeSchema,)*> OperationParameter for axum::extract::UrlParams<($($param,)*)> {
-            fn modify_op(_: &mut OpenAPI,op: &mut Operation, _: bool) {
-                let parameters = vec![$(<$param as DescribeSchema>::reference_or_schema(),)*];
-                url_params(op, parameters)
+            fn modify_op(openapi: &mut OpenAPI, op: &mut Operation, _: bool) -> Result<(), OpenapiError> {
+                let mut gen = SchemaGenerator::new(SchemaView::Request);
+                let parameters = vec![$(gen.subschema_for::<$param>(),)*];
+                gen.register_components(openapi)?;
+                url_params(op, parameters);
+                Ok(())
             }
         }
     };
@@ -76,41 +211,110 @@ fn url_params(op: &mut Operation, parameters: Vec<ReferenceOr<Schema>>) {
 all_tuples!(impl_url_params, 1, 6, T);
 
 impl<T: DescribeSchema> OperationParameter for axum::extract::Query<T> {
-    fn modify_op(_: &mut OpenAPI, op: &mut Operation, required: bool) {
-        let schema = T::describe_schema();
-        let obj = match schema.schema_kind {
-            SchemaKind::Type(Type::Object(obj)) => obj,
-            _ => panic!("unsupported schema for query parameters"),
-        };
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        op: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError> {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        let schema = T::describe_schema(&mut gen);
+        gen.register_components(openapi)?;
 
-        for (name, schema) in &obj.properties {
-            op.parameters.push(ReferenceOr::Item(Parameter::Query {
-                parameter_data: ParameterData {
-                    name: name.clone(),
-                    description: None,
-                    required,
-                    deprecated: None,
-                    format: ParameterSchemaOrContent::Schema(match schema.clone() {
-                        ReferenceOr::Reference { reference } => {
-                            ReferenceOr::Reference { reference }
-                        }
-                        ReferenceOr::Item(item) => ReferenceOr::Item(*item),
-                    }),
-                    example: None,
-                    examples: Default::default(),
-                    explode: None,
-                    extensions: Default::default(),
-                },
-                allow_reserved: false,
-                style: QueryStyle::default(),
-                allow_empty_value: None,
-            }))
+        query_parameters(openapi, op, ReferenceOr::Item(schema), required).ok_or(
+            OpenapiError::UnsupportedQuery {
+                type_name: std::any::type_name::<T>(),
+            },
+        )
+    }
+}
+
+/// Adds a query parameter for every property of an object schema, or returns `None` if the schema is not an object
+fn query_parameters(
+    openapi: &OpenAPI,
+    op: &mut Operation,
+    schema: ReferenceOr<Schema>,
+    required: bool,
+) -> Option<()> {
+    let mut properties = Vec::new();
+    query_properties(openapi, schema, true, &mut properties)?;
+
+    for (name, schema, required_property) in properties {
+        op.parameters.push(ReferenceOr::Item(Parameter::Query {
+            parameter_data: ParameterData {
+                name,
+                description: None,
+                required: required && required_property,
+                deprecated: None,
+                format: ParameterSchemaOrContent::Schema(schema),
+                example: None,
+                examples: Default::default(),
+                explode: None,
+                extensions: Default::default(),
+            },
+            allow_reserved: false,
+            style: QueryStyle::default(),
+            allow_empty_value: None,
+        }))
+    }
+    Some(())
+}
+
+/// Collects the properties of an object schema and whether they are required.
+///
+/// `#[serde(flatten)]`ed types are part of an `allOf`, whose objects and referenced components are merged.
+/// Returns `None` if the schema is not an object.
+fn query_properties(
+    openapi: &OpenAPI,
+    schema: ReferenceOr<Schema>,
+    required: bool,
+    properties: &mut Vec<(String, ReferenceOr<Schema>, bool)>,
+) -> Option<()> {
+    let schema = match schema {
+        ReferenceOr::Item(schema) => schema,
+        ReferenceOr::Reference { reference } => {
+            let name = reference.strip_prefix("#/components/schemas/")?;
+            match openapi.components.as_ref()?.schemas.get(name)? {
+                ReferenceOr::Item(schema) => schema.clone(),
+                ReferenceOr::Reference { .. } => return None,
+            }
+        }
+    };
+
+    match schema.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => {
+            for (name, schema) in object.properties {
+                let schema = match schema {
+                    ReferenceOr::Reference { reference } => ReferenceOr::Reference { reference },
+                    ReferenceOr::Item(item) => ReferenceOr::Item(*item),
+                };
+                let required = required && object.required.contains(&name);
+                properties.push((name, schema, required));
+            }
         }
+        SchemaKind::AllOf { all_of } => {
+            for schema in all_of {
+                query_properties(openapi, schema, required, properties)?;
+            }
+        }
+        // an optional flattened schema, which is an alternative to the empty schema
+        SchemaKind::AnyOf { any_of } => {
+            for schema in any_of {
+                match schema {
+                    ReferenceOr::Item(Schema {
+                        schema_kind: SchemaKind::Any(_),
+                        ..
+                    }) => {}
+                    schema => query_properties(openapi, schema, false, properties)?,
+                }
+            }
+        }
+        _ => return None,
     }
+    Some(())
 }
 
 impl OperationResult for () {
-    fn modify_op(_: &mut OpenAPI, operation: &mut Operation) {
+    fn modify_op(_: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
         operation.responses.default = Some(ReferenceOr::Item(Response {
             description: "Default OK response".to_string(),
             headers: Default::default(),
@@ -118,9 +322,449 @@ impl OperationResult for () {
             links: Default::default(),
             extensions: Default::default(),
         }));
+        Ok(())
     }
 }
 
 impl<Body> OperationResult for hyper::Response<Body> {
-    fn modify_op(_: &mut OpenAPI, _: &mut Operation) {}
+    fn modify_op(_: &mut OpenAPI, _: &mut Operation) -> Result<(), OpenapiError> {
+        Ok(())
+    }
+}
+
+impl<T: DescribeSchema> OperationResult for axum::response::Json<T> {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        let mut gen = SchemaGenerator::new(SchemaView::Response);
+        let schema = gen.subschema_for::<T>();
+        gen.register_components(openapi)?;
+
+        utils::insert_response(
+            &mut operation.responses,
+            utils::response(200, None, Some(schema)),
+        );
+        Ok(())
+    }
+}
+
+impl<T: OperationResult, E: DescribeResponses> OperationResult for Result<T, E> {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        T::modify_op(openapi, operation)?;
+
+        let mut gen = SchemaGenerator::new(SchemaView::Response);
+        let responses = E::describe_responses(&mut gen);
+        let default = E::describe_default_response(&mut gen);
+        gen.register_components(openapi)?;
+
+        for response in responses {
+            utils::insert_response(&mut operation.responses, response);
+        }
+        if let Some(default) = default {
+            utils::insert_default_response(&mut operation.responses, default);
+        }
+        Ok(())
+    }
+}
+
+fn error_response() -> Response {
+    Response {
+        description: "Error response".to_string(),
+        ..Default::default()
+    }
+}
+
+impl DescribeResponses for hyper::StatusCode {
+    fn describe_responses(_: &mut SchemaGenerator) -> Vec<(StatusCode, Response)> {
+        Vec::new()
+    }
+
+    fn describe_default_response(_: &mut SchemaGenerator) -> Option<Response> {
+        Some(error_response())
+    }
+}
+
+/// The body `T` with a status code which is only known at runtime
+impl<T: DescribeResponses> DescribeResponses for (hyper::StatusCode, T) {
+    fn describe_responses(_: &mut SchemaGenerator) -> Vec<(StatusCode, Response)> {
+        Vec::new()
+    }
+
+    fn describe_default_response(gen: &mut SchemaGenerator) -> Option<Response> {
+        let mut default = error_response();
+        let bodies = T::describe_responses(gen)
+            .into_iter()
+            .map(|(_, response)| response)
+            .chain(T::describe_default_response(gen));
+        for body in bodies {
+            default.headers.extend(body.headers);
+            default.content.extend(body.content);
+        }
+        Some(default)
+    }
+}
+
+impl<T: DescribeSchema> DescribeResponses for axum::response::Json<T> {
+    fn describe_responses(gen: &mut SchemaGenerator) -> Vec<(StatusCode, Response)> {
+        vec![utils::response(200, None, Some(gen.subschema_for::<T>()))]
+    }
+}
+
+// axum responds to string errors with `200 OK`, just like to successful ones
+impl DescribeResponses for String {
+    fn describe_responses(_: &mut SchemaGenerator) -> Vec<(StatusCode, Response)> {
+        vec![utils::content_response(
+            "text/plain",
+            utils::string_schema(None),
+        )]
+    }
+}
+
+impl DescribeResponses for &'static str {
+    fn describe_responses(gen: &mut SchemaGenerator) -> Vec<(StatusCode, Response)> {
+        String::describe_responses(gen)
+    }
+}
+
+impl OperationResult for hyper::StatusCode {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        dynamic_status::<()>(openapi, operation)
+    }
+}
+
+impl OperationResult for hyper::HeaderMap {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        <()>::modify_op(openapi, operation)
+    }
+}
+
+impl<T: OperationResult> OperationResult for (hyper::StatusCode, T) {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        dynamic_status::<T>(openapi, operation)
+    }
+}
+
+impl<T: OperationResult> OperationResult for (hyper::HeaderMap, T) {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        T::modify_op(openapi, operation)
+    }
+}
+
+impl<T: OperationResult> OperationResult for (hyper::StatusCode, hyper::HeaderMap, T) {
+    fn modify_op(openapi: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+        dynamic_status::<T>(openapi, operation)
+    }
+}
+
+/// Documents the responses of `T` as the `default` response, because the status code is only known at runtime.
+///
+/// The actual status codes can be declared using [`HandlerExt::with_status`](crate::openapi_adapters::HandlerExt::with_status).
+fn dynamic_status<T: OperationResult>(
+    openapi: &mut OpenAPI,
+    operation: &mut Operation,
+) -> Result<(), OpenapiError> {
+    let mut inner = Operation::default();
+    T::modify_op(openapi, &mut inner)?;
+
+    let responses = inner.responses.default.into_iter().chain(
+        inner
+            .responses
+            .responses
+            .into_iter()
+            .map(|(_, response)| response),
+    );
+    for response in responses {
+        if let ReferenceOr::Item(mut response) = response {
+            response.description = "Default response".to_string();
+            utils::insert_default_response(&mut operation.responses, response);
+        }
+    }
+    Ok(())
+}
+
+macro_rules! impl_content_result {
+    ( $( [$($generics:tt)*] $ty:ty => $media_type:literal $(, format: $format:literal)?; )* ) => {
+        $(
+            impl<$($generics)*> OperationResult for $ty {
+                fn modify_op(_: &mut OpenAPI, operation: &mut Operation) -> Result<(), OpenapiError> {
+                    let schema = utils::string_schema(None $(.or(Some($format)))?);
+                    utils::insert_response(
+                        &mut operation.responses,
+                        utils::content_response($media_type, schema),
+                    );
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+impl_content_result! {
+    [] String => "text/plain";
+    [] &'static str => "text/plain";
+    [] std::borrow::Cow<'static, str> => "text/plain";
+    [T] axum::response::Html<T> => "text/html";
+    [] Vec<u8> => "application/octet-stream", format: "binary";
+    [] &'static [u8] => "application/octet-stream", format: "binary";
+    [] std::borrow::Cow<'static, [u8]> => "application/octet-stream", format: "binary";
+    [] hyper::body::Bytes => "application/octet-stream", format: "binary";
+}
+
+#[cfg(feature = "typed-header")]
+impl<H: headers::Header + DescribeHeader> OperationParameter for axum::extract::TypedHeader<H> {
+    fn modify_op(
+        openapi: &mut OpenAPI,
+        op: &mut Operation,
+        required: bool,
+    ) -> Result<(), OpenapiError> {
+        if let Some(scheme) = H::security_scheme() {
+            utils::add_security_requirement(openapi, op, scheme, Vec::new(), required);
+            return Ok(());
+        }
+        // OpenAPI ignores header parameters with these names, they are described by the request body and security schemes
+        if ["accept", "content-type", "authorization"].contains(&H::name().as_str()) {
+            return Ok(());
+        }
+
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        let schema = H::describe_header(&mut gen);
+        gen.register_components(openapi)?;
+
+        op.parameters.push(ReferenceOr::Item(Parameter::Header {
+            parameter_data: ParameterData {
+                name: H::name().to_string(),
+                description: None,
+                required,
+                deprecated: None,
+                format: ParameterSchemaOrContent::Schema(ReferenceOr::Item(schema)),
+                example: None,
+                examples: Default::default(),
+                explode: None,
+                extensions: Default::default(),
+            },
+            style: HeaderStyle::Simple,
+        }));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `struct Base { page: u32 }`
+    struct Base;
+    impl DescribeSchema for Base {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            utils::object_schema(
+                vec![("page".to_string(), gen.subschema_for::<u32>())],
+                vec!["page".to_string()],
+            )
+        }
+        fn ref_name() -> Option<String> {
+            Some("Base".to_string())
+        }
+    }
+
+    /// `struct Search { query: Option<String>, #[serde(flatten)] base: Base }`
+    struct Search;
+    impl DescribeSchema for Search {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            let object = utils::object_schema(
+                vec![("query".to_string(), gen.subschema_for::<Option<String>>())],
+                vec![],
+            );
+            utils::flatten_schema(object, vec![(gen.subschema_for::<Base>(), false)])
+        }
+    }
+
+    #[test]
+    fn query_merges_flattened_components() {
+        let mut openapi = OpenAPI::default();
+        let mut op = Operation::default();
+        axum::extract::Query::<Search>::modify_op(&mut openapi, &mut op, true).unwrap();
+        assert_eq!(parameter_names(&op), vec![("page", true), ("query", false)]);
+    }
+
+    /// `struct Filter { query: Option<String>, #[serde(flatten)] base: Option<Base> }`
+    struct Filter;
+    impl DescribeSchema for Filter {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            let object = utils::object_schema(
+                vec![("query".to_string(), gen.subschema_for::<Option<String>>())],
+                vec![],
+            );
+            utils::flatten_schema(object, vec![(gen.subschema_for::<Option<Base>>(), true)])
+        }
+    }
+
+    fn parameter_names(op: &Operation) -> Vec<(&str, bool)> {
+        op.parameters
+            .iter()
+            .map(|parameter| match parameter {
+                ReferenceOr::Item(Parameter::Query { parameter_data, .. }) => {
+                    (parameter_data.name.as_str(), parameter_data.required)
+                }
+                _ => panic!("expected a query parameter"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn query_of_optional_flattened_components_is_optional() {
+        let mut openapi = OpenAPI::default();
+        let mut op = Operation::default();
+        axum::extract::Query::<Filter>::modify_op(&mut openapi, &mut op, true).unwrap();
+        assert_eq!(
+            parameter_names(&op),
+            vec![("page", false), ("query", false)]
+        );
+    }
+
+    #[test]
+    fn query_of_a_non_object_is_an_error() {
+        let result = axum::extract::Query::<Vec<String>>::modify_op(
+            &mut OpenAPI::default(),
+            &mut Operation::default(),
+            true,
+        );
+        assert_eq!(
+            result,
+            Err(OpenapiError::UnsupportedQuery {
+                type_name: std::any::type_name::<Vec<String>>()
+            })
+        );
+    }
+
+    #[cfg(feature = "multipart")]
+    #[test]
+    fn multipart_forms_describe_their_parts() {
+        use crate::multipart::{File, MultipartForm};
+
+        #[derive(crate::DescribeMultipart)]
+        #[allow(dead_code)]
+        struct Upload {
+            /// The name of the pet
+            name: String,
+            #[openapi(content_type = "image/png, image/jpeg")]
+            picture: File,
+            attachments: Option<Vec<File>>,
+        }
+
+        let mut openapi = OpenAPI::default();
+        let mut op = Operation::default();
+        MultipartForm::<Upload>::modify_op(&mut openapi, &mut op, true).unwrap();
+        let body = match op.request_body {
+            Some(ReferenceOr::Item(body)) => body,
+            _ => panic!("expected a request body"),
+        };
+        assert!(body.required);
+        let media_type = &body.content["multipart/form-data"];
+
+        let encoding: Vec<_> = media_type
+            .encoding
+            .iter()
+            .map(|(part, encoding)| (part.as_str(), encoding.content_type.as_deref()))
+            .collect();
+        assert_eq!(encoding, vec![("picture", Some("image/png, image/jpeg"))]);
+
+        let object = match &media_type.schema {
+            Some(ReferenceOr::Item(Schema {
+                schema_kind: SchemaKind::Type(Type::Object(object)),
+                ..
+            })) => object,
+            _ => panic!("expected an object schema"),
+        };
+        assert_eq!(object.required, vec!["name", "picture"]);
+        let property = |name: &str| match &object.properties[name] {
+            ReferenceOr::Item(schema) => schema.clone(),
+            _ => panic!("expected an inline schema"),
+        };
+        assert_eq!(
+            property("name").schema_data.description.as_deref(),
+            Some("The name of the pet")
+        );
+        assert_eq!(
+            property("picture").schema_kind,
+            SchemaKind::Type(Type::String(StringType {
+                format: utils::string_format("binary"),
+                ..Default::default()
+            }))
+        );
+        assert!(object.properties.contains_key("attachments"));
+    }
+
+    #[test]
+    fn undocumented_extractors_leave_the_operation_unchanged() {
+        use crate::openapi_traits::OpenapiApp;
+        use axum::{extract::Extension, routing::RoutingDsl};
+
+        async fn documented(_: String) {}
+        async fn undocumented(_: hyper::HeaderMap, _: Extension<u32>, _: hyper::Method, _: String) {
+        }
+        let app = axum::route(
+            "/documented",
+            axum::handler::get::<_, hyper::Body, _>(documented),
+        )
+        .route(
+            "/undocumented",
+            axum::handler::post::<_, hyper::Body, _>(undocumented),
+        );
+        let openapi = app.try_openapi().unwrap();
+        // the extractors still register as an app serving requests
+        let _ = app
+            .layer(axum::AddExtensionLayer::new(0u32))
+            .into_make_service();
+        let path_item = |path: &str| match &openapi.paths[path] {
+            ReferenceOr::Item(path_item) => path_item.clone(),
+            _ => panic!("expected a path item"),
+        };
+
+        let documented = path_item("/documented").get.unwrap();
+        let undocumented = path_item("/undocumented").post.unwrap();
+        assert!(undocumented.parameters.is_empty());
+        assert_eq!(undocumented, documented);
+    }
+
+    #[cfg(feature = "typed-header")]
+    #[test]
+    fn typed_headers_are_parameters_or_security_requirements() {
+        use crate::openapi_traits::OpenapiApp;
+        use axum::extract::TypedHeader;
+        use headers::authorization::{Authorization, Bearer};
+
+        async fn handler(
+            _: TypedHeader<Authorization<Bearer>>,
+            _: TypedHeader<headers::ContentType>,
+            _: Option<TypedHeader<headers::UserAgent>>,
+        ) {
+        }
+        let app = axum::route("/pets", axum::handler::get::<_, hyper::Body, _>(handler));
+        let openapi = app.try_openapi().unwrap();
+        let op = match &openapi.paths["/pets"] {
+            ReferenceOr::Item(path_item) => path_item.get.clone().unwrap(),
+            _ => panic!("expected a path item"),
+        };
+
+        let parameters: Vec<_> = op
+            .parameters
+            .iter()
+            .map(|parameter| match parameter {
+                ReferenceOr::Item(Parameter::Header { parameter_data, .. }) => {
+                    (parameter_data.name.as_str(), parameter_data.required)
+                }
+                _ => panic!("expected a header parameter"),
+            })
+            .collect();
+        assert_eq!(parameters, vec![("user-agent", false)]);
+
+        let mut requirement = SecurityRequirement::new();
+        requirement.insert("bearerAuth".to_string(), Vec::new());
+        assert_eq!(op.security, Some(vec![requirement]));
+        let schemes = openapi.components.unwrap().security_schemes;
+        assert_eq!(
+            schemes["bearerAuth"],
+            ReferenceOr::Item(SecurityScheme::HTTP {
+                scheme: "bearer".to_string(),
+                bearer_format: None,
+            })
+        );
+    }
 }
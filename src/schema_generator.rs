@@ -0,0 +1,414 @@
+use std::collections::{HashMap, HashSet};
+
+use openapiv3::*;
+
+use crate::error::OpenapiError;
+use crate::openapi_traits::{DescribeSchema, SchemaView};
+
+type DescribeFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Context for describing types as [`openapiv3::Schema`]s.
+///
+/// Types with a [`ref_name`](DescribeSchema::ref_name) are referenced using `$ref`,
+/// and every named type which is reachable is collected exactly once for the `components/schemas` section.
+///
+/// Recursive types are referenced while they are being described. Types without a `ref_name` which turn out to be
+/// recursive, like `#[serde(transparent)] struct Tree(Vec<Tree>)`, are registered under their [`type_name`](DescribeSchema::type_name).
+///
+/// In the [`SchemaView::Request`] view, a named type whose schema differs from the response view,
+/// or which references such a type, is registered as `{ref_name}Request`.
+/// If that name is also the `ref_name` of another type, [`register_components`](SchemaGenerator::register_components) fails.
+pub struct SchemaGenerator {
+    view: SchemaView,
+    schemas: Vec<(String, Schema)>,
+    registered: HashSet<String>,
+    /// The component names which were derived from another name by adding the `Request` suffix, and those which were not
+    suffixed: HashSet<String>,
+    unsuffixed: HashSet<String>,
+    /// Component names which were used both with and without the `Request` suffix
+    conflicts: Vec<String>,
+    /// The component names of named types in the request view, by `ref_name`
+    request_names: HashMap<String, String>,
+    /// The types without a `ref_name` which are currently being described inline, by [`std::any::type_name`]
+    describing: Vec<&'static str>,
+    /// The component names of the types in `describing` which were referenced recursively
+    recursive: HashMap<&'static str, String>,
+    /// While comparing the views of a type, named types are referenced by their `ref_name` and collected here instead of being described
+    dependencies: Option<Vec<(String, DescribeFn)>>,
+}
+
+impl SchemaGenerator {
+    pub fn new(view: SchemaView) -> Self {
+        SchemaGenerator {
+            view,
+            schemas: Vec::new(),
+            registered: HashSet::new(),
+            suffixed: HashSet::new(),
+            unsuffixed: HashSet::new(),
+            conflicts: Vec::new(),
+            request_names: HashMap::new(),
+            describing: Vec::new(),
+            recursive: HashMap::new(),
+            dependencies: None,
+        }
+    }
+
+    pub fn view(&self) -> SchemaView {
+        self.view
+    }
+
+    /// The schema of `T`, which is a reference to its component if it has a `ref_name`.
+    pub fn subschema_for<T: DescribeSchema + ?Sized>(&mut self) -> ReferenceOr<Schema> {
+        let ref_name = match T::ref_name() {
+            Some(ref_name) => ref_name,
+            None => return self.describe_inline::<T>(),
+        };
+
+        if let Some(dependencies) = &mut self.dependencies {
+            dependencies.push((ref_name.clone(), T::describe_schema));
+            return reference(&ref_name);
+        }
+
+        let component_name = self.component_name(ref_name.clone(), T::describe_schema);
+        self.check_suffix(&component_name, component_name != ref_name);
+        if self.registered.insert(component_name.clone()) {
+            let schema = T::describe_schema(self);
+            self.schemas.push((component_name.clone(), schema));
+        }
+        reference(&component_name)
+    }
+
+    /// Describes a type without a `ref_name`, unless it is already being described further up
+    fn describe_inline<T: DescribeSchema + ?Sized>(&mut self) -> ReferenceOr<Schema> {
+        let key = std::any::type_name::<T>();
+        if self.describing.contains(&key) {
+            let component_name = match self.recursive.get(key) {
+                Some(component_name) => component_name.clone(),
+                None => {
+                    let component_name = self.recursive_name::<T>();
+                    self.check_suffix(&component_name, component_name != T::type_name());
+                    self.recursive.insert(key, component_name.clone());
+                    component_name
+                }
+            };
+            return reference(&component_name);
+        }
+
+        self.describing.push(key);
+        let schema = T::describe_schema(self);
+        self.describing.pop();
+
+        match self.recursive.remove(key) {
+            Some(component_name) => {
+                if self.registered.insert(component_name.clone()) {
+                    self.schemas.push((component_name.clone(), schema));
+                }
+                reference(&component_name)
+            }
+            None => ReferenceOr::Item(schema),
+        }
+    }
+
+    /// The component name of a recursive type without a `ref_name`
+    fn recursive_name<T: DescribeSchema + ?Sized>(&mut self) -> String {
+        let type_name = T::type_name();
+        if self.view == SchemaView::Response || self.dependencies.is_some() {
+            return type_name;
+        }
+
+        let (request, dependencies) =
+            Self::describe_shallow(SchemaView::Request, T::describe_schema);
+        let (response, _) = Self::describe_shallow(SchemaView::Response, T::describe_schema);
+        let differs = request != response
+            || dependencies
+                .into_iter()
+                .any(|(name, describe)| self.component_name(name.clone(), describe) != name);
+        match differs {
+            true => format!("{}Request", type_name),
+            false => type_name,
+        }
+    }
+
+    /// The schemas of all named types described so far, in the order they were finished
+    pub fn into_schemas(self) -> Vec<(String, Schema)> {
+        self.schemas
+    }
+
+    /// Adds the schemas of all named types to the `components/schemas` of `openapi`, unless they are already present.
+    ///
+    /// Fails if a name is already used for a different schema.
+    pub fn register_components(self, openapi: &mut OpenAPI) -> Result<(), OpenapiError> {
+        if let Some(name) = self.conflicts.into_iter().next() {
+            return Err(OpenapiError::ConflictingSchema { name });
+        }
+
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (name, schema) in self.schemas {
+            match components.schemas.get(&name) {
+                None => {
+                    components.schemas.insert(name, ReferenceOr::Item(schema));
+                }
+                Some(ReferenceOr::Item(existing)) if *existing == schema => {}
+                Some(_) => return Err(OpenapiError::ConflictingSchema { name }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Remembers whether a component name has the `Request` suffix added by this generator, to detect when it is also the name of another type
+    fn check_suffix(&mut self, component_name: &str, suffixed: bool) {
+        let (set, other) = match suffixed {
+            true => (&mut self.suffixed, &self.unsuffixed),
+            false => (&mut self.unsuffixed, &self.suffixed),
+        };
+        if other.contains(component_name) && !self.conflicts.iter().any(|c| c == component_name) {
+            self.conflicts.push(component_name.to_string());
+        }
+        set.insert(component_name.to_string());
+    }
+
+    fn component_name(&mut self, ref_name: String, describe: DescribeFn) -> String {
+        if self.view == SchemaView::Response {
+            return ref_name;
+        }
+        if let Some(name) = self.request_names.get(&ref_name) {
+            return name.clone();
+        }
+
+        // describe every reachable named type in both views, referencing other named types by their plain `ref_name`
+        let mut graph: Vec<(String, bool, Vec<String>)> = Vec::new();
+        let mut queue = vec![(ref_name.clone(), describe)];
+        while let Some((name, describe)) = queue.pop() {
+            if self.request_names.contains_key(&name) || graph.iter().any(|(n, ..)| *n == name) {
+                continue;
+            }
+            let (request, mut dependencies) = Self::describe_shallow(SchemaView::Request, describe);
+            let (response, response_dependencies) =
+                Self::describe_shallow(SchemaView::Response, describe);
+            dependencies.extend(response_dependencies);
+
+            let names = dependencies.iter().map(|(name, _)| name.clone()).collect();
+            graph.push((name, request != response, names));
+            queue.extend(dependencies);
+        }
+
+        // a type differs if it references a type which differs
+        loop {
+            let differing: Vec<String> = graph
+                .iter()
+                .filter(|(_, differs, _)| *differs)
+                .map(|(name, ..)| name.clone())
+                .collect();
+            let mut changed = false;
+            for (_, differs, dependencies) in &mut graph {
+                if !*differs
+                    && dependencies.iter().any(|dependency| {
+                        let known = self.request_names.get(dependency);
+                        differing.contains(dependency)
+                            || matches!(known, Some(known) if known != dependency)
+                    })
+                {
+                    *differs = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        for (name, differs, _) in graph {
+            let request_name = match differs {
+                true => format!("{}Request", name),
+                false => name.clone(),
+            };
+            self.request_names.insert(name, request_name);
+        }
+        self.request_names[&ref_name].clone()
+    }
+
+    fn describe_shallow(
+        view: SchemaView,
+        describe: DescribeFn,
+    ) -> (Schema, Vec<(String, DescribeFn)>) {
+        let mut gen = SchemaGenerator::new(view);
+        gen.dependencies = Some(Vec::new());
+        let schema = describe(&mut gen);
+        (schema, gen.dependencies.unwrap_or_default())
+    }
+}
+
+fn reference(component_name: &str) -> ReferenceOr<Schema> {
+    ReferenceOr::ref_(&format!("#/components/schemas/{}", component_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils;
+
+    struct Inner;
+    impl DescribeSchema for Inner {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            let required = match gen.view() {
+                SchemaView::Request => vec![],
+                SchemaView::Response => vec!["a".to_string()],
+            };
+            utils::object_schema(
+                vec![("a".to_string(), gen.subschema_for::<String>())],
+                required,
+            )
+        }
+        fn ref_name() -> Option<String> {
+            Some("Inner".to_string())
+        }
+    }
+
+    struct Outer;
+    impl DescribeSchema for Outer {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            let properties = vec![
+                ("first".to_string(), gen.subschema_for::<Inner>()),
+                ("second".to_string(), gen.subschema_for::<Vec<Inner>>()),
+            ];
+            utils::object_schema(properties, vec![])
+        }
+        fn ref_name() -> Option<String> {
+            Some("Outer".to_string())
+        }
+    }
+
+    /// A type whose name is the request name of `Inner`
+    struct InnerRequest;
+    impl DescribeSchema for InnerRequest {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            utils::object_schema(vec![("id".to_string(), gen.subschema_for::<i64>())], vec![])
+        }
+        fn ref_name() -> Option<String> {
+            Some("InnerRequest".to_string())
+        }
+    }
+
+    /// `struct Category { children: Vec<Category> }`
+    struct Category;
+    impl DescribeSchema for Category {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            utils::object_schema(
+                vec![("children".to_string(), gen.subschema_for::<Vec<Category>>())],
+                vec!["children".to_string()],
+            )
+        }
+        fn ref_name() -> Option<String> {
+            Some("Category".to_string())
+        }
+    }
+
+    /// `#[serde(transparent)] struct Tree(Vec<Tree>)`
+    struct Tree;
+    impl DescribeSchema for Tree {
+        fn describe_schema(gen: &mut SchemaGenerator) -> Schema {
+            Vec::<Tree>::describe_schema(gen)
+        }
+        fn type_name() -> String {
+            "Tree".to_string()
+        }
+    }
+
+    fn names(gen: SchemaGenerator) -> Vec<String> {
+        gen.into_schemas()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn registers_nested_schemas_once() {
+        let mut gen = SchemaGenerator::new(SchemaView::Response);
+        assert_eq!(gen.subschema_for::<Outer>(), reference("Outer"));
+        assert_eq!(names(gen), vec!["Inner", "Outer"]);
+    }
+
+    #[test]
+    fn request_names_propagate_to_referencing_types() {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        assert_eq!(gen.subschema_for::<Outer>(), reference("OuterRequest"));
+        assert_eq!(names(gen), vec!["InnerRequest", "OuterRequest"]);
+    }
+
+    #[test]
+    fn recursive_types_are_referenced() {
+        for view in [SchemaView::Request, SchemaView::Response] {
+            let mut gen = SchemaGenerator::new(view);
+            assert_eq!(gen.subschema_for::<Category>(), reference("Category"));
+            let schemas = gen.into_schemas();
+            assert_eq!(schemas.len(), 1);
+            let items = match &schemas[0].1.schema_kind {
+                SchemaKind::Type(Type::Object(object)) => &object.properties["children"],
+                _ => panic!("expected an object"),
+            };
+            let array = utils::ty_schema(Type::Array(ArrayType {
+                items: ReferenceOr::ref_("#/components/schemas/Category"),
+                min_items: None,
+                max_items: None,
+                unique_items: false,
+            }));
+            assert_eq!(items, &ReferenceOr::Item(Box::new(array)));
+        }
+    }
+
+    #[test]
+    fn recursive_types_without_ref_name_are_registered() {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        assert_eq!(gen.subschema_for::<Tree>(), reference("Tree"));
+        assert_eq!(names(gen), vec!["Tree"]);
+    }
+
+    #[test]
+    fn request_names_conflicting_across_generators_are_an_error() {
+        let mut openapi = OpenAPI::default();
+
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        assert_eq!(gen.subschema_for::<Inner>(), reference("InnerRequest"));
+        gen.register_components(&mut openapi).unwrap();
+
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        assert_eq!(
+            gen.subschema_for::<InnerRequest>(),
+            reference("InnerRequest")
+        );
+        assert_eq!(
+            gen.register_components(&mut openapi),
+            Err(OpenapiError::ConflictingSchema {
+                name: "InnerRequest".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn request_names_conflicting_in_one_generator_are_an_error() {
+        let mut gen = SchemaGenerator::new(SchemaView::Request);
+        gen.subschema_for::<InnerRequest>();
+        gen.subschema_for::<Inner>();
+        assert_eq!(
+            gen.register_components(&mut OpenAPI::default()),
+            Err(OpenapiError::ConflictingSchema {
+                name: "InnerRequest".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn identical_schemas_are_registered_once() {
+        let mut openapi = OpenAPI::default();
+        for _ in 0..2 {
+            let mut gen = SchemaGenerator::new(SchemaView::Request);
+            gen.subschema_for::<Outer>();
+            gen.register_components(&mut openapi).unwrap();
+        }
+        let components = openapi.components.unwrap();
+        assert_eq!(
+            components.schemas.keys().collect::<Vec<_>>(),
+            vec!["InnerRequest", "OuterRequest"]
+        );
+    }
+}
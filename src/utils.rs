@@ -1,5 +1,6 @@
 use openapiv3::*;
-use serde::Serialize;
+
+use crate::openapi_traits::{ArraySchema, Number, NumberConstraints, NumberSchema, StringSchema};
 
 pub fn ty_schema(ty: Type) -> Schema {
     Schema {
@@ -11,22 +12,793 @@ pub fn ty_schema(ty: Type) -> Schema {
     }
 }
 
-pub fn yaml_response<T: Serialize>(body: &T) -> hyper::Response<hyper::Body> {
-    let bytes = match serde_yaml::to_vec(body) {
-        Ok(res) => res,
-        Err(err) => {
-            return hyper::Response::builder()
-                .status(hyper::StatusCode::INTERNAL_SERVER_ERROR)
-                .header(hyper::header::CONTENT_TYPE, "text/plain")
-                .body(hyper::Body::from(err.to_string()))
-                .unwrap();
+fn kind_schema(schema_kind: SchemaKind) -> Schema {
+    Schema {
+        schema_data: Default::default(),
+        schema_kind,
+    }
+}
+
+pub fn object_schema(
+    properties: Vec<(String, ReferenceOr<Schema>)>,
+    required: Vec<String>,
+) -> Schema {
+    ty_schema(Type::Object(ObjectType {
+        properties: properties
+            .into_iter()
+            .map(|(name, schema)| (name, boxed(schema)))
+            .collect(),
+        required,
+        additional_properties: None,
+        min_properties: None,
+        max_properties: None,
+    }))
+}
+
+/// Adds `#[serde(alias = "...")]` names of properties to an object schema of a request.
+///
+/// Every `(name, alias)` adds a property `alias` with the schema of `name`.
+/// `name` is no longer required, because the value may be sent under any of its names.
+pub fn alias_properties(mut schema: Schema, aliases: &[(&str, &str)]) -> Schema {
+    if let SchemaKind::Type(Type::Object(object)) = &mut schema.schema_kind {
+        for (name, alias) in aliases {
+            if let Some(property) = object.properties.get(*name).cloned() {
+                object.properties.insert(alias.to_string(), property);
+                object.required.retain(|required| required != name);
+            }
+        }
+    }
+    schema
+}
+
+pub fn string_enum_schema(values: &[&str]) -> Schema {
+    ty_schema(Type::String(StringType {
+        enumeration: values.iter().map(|value| value.to_string()).collect(),
+        ..Default::default()
+    }))
+}
+
+/// A fixed-length array. OpenAPI 3.0 has no way to describe each position on its own, so the items are a `oneOf` of all element schemas.
+pub fn tuple_schema(items: Vec<ReferenceOr<Schema>>) -> Schema {
+    let len = items.len();
+    let mut unique: Vec<ReferenceOr<Schema>> = Vec::with_capacity(len);
+    for item in items {
+        if !unique.contains(&item) {
+            unique.push(item);
+        }
+    }
+    let items = match unique.len() {
+        0 => ReferenceOr::Item(kind_schema(SchemaKind::Any(Default::default()))),
+        1 => unique.remove(0),
+        _ => ReferenceOr::Item(one_of_schema(unique)),
+    };
+
+    ty_schema(Type::Array(ArrayType {
+        items: boxed(items),
+        min_items: Some(len),
+        max_items: Some(len),
+        unique_items: false,
+    }))
+}
+
+/// An array of unique items, like a `HashSet`
+pub fn set_schema(items: ReferenceOr<Schema>) -> Schema {
+    ty_schema(Type::Array(ArrayType {
+        items: boxed(items),
+        min_items: None,
+        max_items: None,
+        unique_items: true,
+    }))
+}
+
+/// An object with arbitrary keys, like a `HashMap`.
+///
+/// Keys are always strings in JSON, so the format of integer keys like `HashMap<u32, T>` is documented in the description.
+pub fn map_schema(key: Schema, value: ReferenceOr<Schema>) -> Schema {
+    let mut schema = ty_schema(Type::Object(ObjectType {
+        additional_properties: Some(AdditionalProperties::Schema(Box::new(value))),
+        ..Default::default()
+    }));
+    if let SchemaKind::Type(Type::Integer(integer)) = key.schema_kind {
+        schema.schema_data.description = Some(match integer.format {
+            VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32) => {
+                "The keys are integers in the `int32` format".to_string()
+            }
+            VariantOrUnknownOrEmpty::Item(IntegerFormat::Int64) => {
+                "The keys are integers in the `int64` format".to_string()
+            }
+            VariantOrUnknownOrEmpty::Unknown(format) => {
+                format!("The keys are integers in the `{}` format", format)
+            }
+            VariantOrUnknownOrEmpty::Empty => "The keys are integers".to_string(),
+        });
+    }
+    schema
+}
+
+pub fn one_of_schema(one_of: Vec<ReferenceOr<Schema>>) -> Schema {
+    kind_schema(SchemaKind::OneOf { one_of })
+}
+
+pub fn all_of_schema(all_of: Vec<ReferenceOr<Schema>>) -> Schema {
+    kind_schema(SchemaKind::AllOf { all_of })
+}
+
+/// The schema of `()` and unit structs, which serialize as `null`
+pub fn unit_schema() -> Schema {
+    Schema {
+        schema_data: SchemaData {
+            nullable: true,
+            ..Default::default()
+        },
+        schema_kind: SchemaKind::Any(Default::default()),
+    }
+}
+
+/// Combines an object schema with the schemas of its `#[serde(flatten)]` fields.
+///
+/// Inline object schemas are merged into the object, while everything else (like references) is composed using `allOf`.
+/// The properties of optional flattened fields, e.g. an `Option` or a field with a default, are not added to `required`,
+/// and optional composed schemas are an `anyOf` with the empty schema, because the fields may be missing entirely.
+/// Flattened maps become the `additionalProperties` of the object.
+pub fn flatten_schema(object: Schema, flattened: Vec<(ReferenceOr<Schema>, bool)>) -> Schema {
+    let mut all_of = Vec::new();
+    let mut object = match object.schema_kind {
+        SchemaKind::Type(Type::Object(object)) => object,
+        _ => unreachable!("flattened fields must be part of an object"),
+    };
+
+    fn compose(all_of: &mut Vec<ReferenceOr<Schema>>, schema: ReferenceOr<Schema>, optional: bool) {
+        let schema = match optional {
+            true => ReferenceOr::Item(kind_schema(SchemaKind::AnyOf {
+                any_of: vec![
+                    schema,
+                    ReferenceOr::Item(kind_schema(SchemaKind::Any(Default::default()))),
+                ],
+            })),
+            false => schema,
+        };
+        if !all_of.contains(&schema) {
+            all_of.push(schema);
+        }
+    }
+
+    fn flatten(
+        object: &mut ObjectType,
+        all_of: &mut Vec<ReferenceOr<Schema>>,
+        flattened: ReferenceOr<Schema>,
+        optional: bool,
+    ) {
+        let schema = match flattened {
+            ReferenceOr::Item(schema) => schema,
+            reference => return compose(all_of, reference, optional),
+        };
+        match schema.schema_kind {
+            SchemaKind::Type(Type::Object(flattened)) => {
+                object.properties.extend(flattened.properties);
+                if !optional {
+                    object.required.extend(flattened.required);
+                }
+                if flattened.additional_properties.is_some() {
+                    object.additional_properties = flattened.additional_properties;
+                }
+            }
+            SchemaKind::AllOf { all_of: flattened } => {
+                for flattened in flattened {
+                    flatten(object, all_of, flattened, optional);
+                }
+            }
+            schema_kind => compose(
+                all_of,
+                ReferenceOr::Item(Schema {
+                    schema_data: schema.schema_data,
+                    schema_kind,
+                }),
+                optional,
+            ),
+        }
+    }
+    for (flattened, optional) in flattened {
+        flatten(&mut object, &mut all_of, flattened, optional);
+    }
+
+    if all_of.is_empty() {
+        return ty_schema(Type::Object(object));
+    }
+    if !object.properties.is_empty() || object.additional_properties.is_some() {
+        all_of.push(ReferenceOr::Item(ty_schema(Type::Object(object))));
+    }
+    all_of_schema(all_of)
+}
+
+/// The schema itself, or a reference wrapped in an `allOf`
+pub fn inline_schema(schema: ReferenceOr<Schema>) -> Schema {
+    match schema {
+        ReferenceOr::Item(schema) => schema,
+        reference => all_of_schema(vec![reference]),
+    }
+}
+
+/// Modifies the [`SchemaData`] of a schema. References are wrapped in an `allOf`, because siblings of `$ref` are ignored.
+pub fn patch_schema_data(
+    schema: ReferenceOr<Schema>,
+    patch: impl FnOnce(&mut SchemaData),
+) -> ReferenceOr<Schema> {
+    let mut schema = inline_schema(schema);
+    patch(&mut schema.schema_data);
+    ReferenceOr::Item(schema)
+}
+
+/// Adds a paragraph to the description, keeping what the schema of the type already describes, like the format of map keys
+pub fn append_description(data: &mut SchemaData, description: &str) {
+    data.description = Some(match data.description.take() {
+        Some(existing) => format!("{}\n\n{}", existing, description),
+        None => description.to_string(),
+    });
+}
+
+/// The inline `Type` of a field schema patched by `#[openapi(...)]` constraints
+fn constrained_type(schema: ReferenceOr<Schema>) -> Schema {
+    match schema {
+        ReferenceOr::Item(schema) if matches!(schema.schema_kind, SchemaKind::Type(_)) => schema,
+        _ => panic!("#[openapi(...)] constraints can only be applied to inline schemas"),
+    }
+}
+
+pub fn constrain_string<T: StringSchema + ?Sized>(
+    schema: ReferenceOr<Schema>,
+    patch: impl FnOnce(&mut StringType),
+) -> ReferenceOr<Schema> {
+    let mut schema = constrained_type(schema);
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => patch(string),
+        _ => panic!("string constraints on a schema which is not a string"),
+    }
+    ReferenceOr::Item(schema)
+}
+
+pub fn constrain_number<T: NumberSchema + ?Sized>(
+    schema: ReferenceOr<Schema>,
+    patch: impl FnOnce(&mut NumberConstraints<T::Number>),
+) -> ReferenceOr<Schema> {
+    let mut constraints = NumberConstraints::default();
+    patch(&mut constraints);
+
+    let mut schema = constrained_type(schema);
+    if let SchemaKind::Type(ty) = &mut schema.schema_kind {
+        T::Number::patch_type(ty, constraints);
+    }
+    ReferenceOr::Item(schema)
+}
+
+/// Sets the `format` of a string, integer or number schema
+pub fn constrain_format(schema: ReferenceOr<Schema>, format: &str) -> ReferenceOr<Schema> {
+    let mut schema = constrained_type(schema);
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::String(string)) => string.format = string_format(format),
+        SchemaKind::Type(Type::Integer(integer)) => integer.format = integer_format(format),
+        SchemaKind::Type(Type::Number(number)) => number.format = number_format(format),
+        _ => panic!("a format on a schema which is not a string, integer or number"),
+    }
+    ReferenceOr::Item(schema)
+}
+
+pub fn constrain_array<T: ArraySchema + ?Sized>(
+    schema: ReferenceOr<Schema>,
+    patch: impl FnOnce(&mut ArrayType),
+) -> ReferenceOr<Schema> {
+    let mut schema = constrained_type(schema);
+    match &mut schema.schema_kind {
+        SchemaKind::Type(Type::Array(array)) => patch(array),
+        _ => panic!("array constraints on a schema which is not an array"),
+    }
+    ReferenceOr::Item(schema)
+}
+
+/// [`std::any::type_name`] without module paths and with punctuation replaced by `_`, e.g. `Vec_Pet` for `Vec<crate::Pet>`
+pub fn type_name<T: ?Sized>() -> String {
+    let mut name = String::new();
+    for segment in
+        std::any::type_name::<T>().split(|c: char| !c.is_alphanumeric() && c != '_' && c != ':')
+    {
+        // the last segment of a path is the name
+        let segment = segment.rsplit("::").next().unwrap_or_default();
+        if segment.is_empty() {
+            continue;
+        }
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.push_str(segment);
+    }
+    name
+}
+
+/// The `format` of an integer schema, which is `int32`, `int64` or a custom one like `uint8`
+pub fn integer_format(format: &str) -> VariantOrUnknownOrEmpty<IntegerFormat> {
+    VariantOrUnknownOrEmpty::Item(match format {
+        "int32" => IntegerFormat::Int32,
+        "int64" => IntegerFormat::Int64,
+        _ => return VariantOrUnknownOrEmpty::Unknown(format.to_string()),
+    })
+}
+
+/// The `format` of a number schema, which is `float`, `double` or a custom one like `decimal`
+pub fn number_format(format: &str) -> VariantOrUnknownOrEmpty<NumberFormat> {
+    VariantOrUnknownOrEmpty::Item(match format {
+        "float" => NumberFormat::Float,
+        "double" => NumberFormat::Double,
+        _ => return VariantOrUnknownOrEmpty::Unknown(format.to_string()),
+    })
+}
+
+/// A `string`, optionally with a `format` (see [`string_format`])
+pub fn string_schema(format: Option<&str>) -> Schema {
+    ty_schema(Type::String(StringType {
+        format: format.map(string_format).unwrap_or_default(),
+        ..Default::default()
+    }))
+}
+
+/// The `format` of a string schema, which is one of the formats known to `openapiv3` or a custom one like `email`
+pub fn string_format(format: &str) -> VariantOrUnknownOrEmpty<StringFormat> {
+    VariantOrUnknownOrEmpty::Item(match format {
+        "date" => StringFormat::Date,
+        "date-time" => StringFormat::DateTime,
+        "password" => StringFormat::Password,
+        "byte" => StringFormat::Byte,
+        "binary" => StringFormat::Binary,
+        _ => return VariantOrUnknownOrEmpty::Unknown(format.to_string()),
+    })
+}
+
+pub fn boxed(schema: ReferenceOr<Schema>) -> ReferenceOr<Box<Schema>> {
+    match schema {
+        ReferenceOr::Reference { reference } => ReferenceOr::Reference { reference },
+        ReferenceOr::Item(item) => ReferenceOr::Item(Box::new(item)),
+    }
+}
+
+pub fn json_media_type(schema: ReferenceOr<Schema>) -> (String, MediaType) {
+    media_type("application/json", schema)
+}
+
+pub fn media_type(media_type: &str, schema: ReferenceOr<Schema>) -> (String, MediaType) {
+    (
+        media_type.to_string(),
+        MediaType {
+            schema: Some(schema),
+            example: None,
+            examples: Default::default(),
+            encoding: Default::default(),
+        },
+    )
+}
+
+/// The canonical reason of a status code, like `Not Found`
+fn status_description(status: u16) -> String {
+    hyper::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// A response with an optional JSON body, described by the canonical reason of the status code unless there is a `description`.
+///
+/// Redirects with a `3xx` status code document the `Location` header.
+pub fn response(
+    status: u16,
+    description: Option<&str>,
+    body: Option<ReferenceOr<Schema>>,
+) -> (StatusCode, Response) {
+    let description = description
+        .map(str::to_string)
+        .unwrap_or_else(|| status_description(status));
+    let mut response = Response {
+        description,
+        headers: Default::default(),
+        content: body.map(json_media_type).into_iter().collect(),
+        links: Default::default(),
+        extensions: Default::default(),
+    };
+    if (300..400).contains(&status) {
+        response
+            .headers
+            .insert("Location".to_string(), location_header());
+    }
+    (StatusCode::Code(status), response)
+}
+
+/// The `Location` header of a redirect
+fn location_header() -> ReferenceOr<Header> {
+    ReferenceOr::Item(Header {
+        description: Some("The URL to redirect to".to_string()),
+        style: HeaderStyle::Simple,
+        required: true,
+        deprecated: None,
+        format: ParameterSchemaOrContent::Schema(ReferenceOr::Item(ty_schema(Type::String(
+            StringType {
+                format: string_format("uri-reference"),
+                ..Default::default()
+            },
+        )))),
+        example: None,
+        examples: Default::default(),
+        extensions: Default::default(),
+    })
+}
+
+/// The `multipart/form-data` media type of an object `schema`, with the content types of some of its parts
+pub fn multipart_media_type(schema: Schema, content_types: Vec<(String, String)>) -> MediaType {
+    MediaType {
+        schema: Some(ReferenceOr::Item(schema)),
+        example: None,
+        examples: Default::default(),
+        encoding: content_types
+            .into_iter()
+            .map(|(part, content_type)| {
+                let encoding = Encoding {
+                    content_type: Some(content_type),
+                    ..Default::default()
+                };
+                (part, encoding)
+            })
+            .collect(),
+    }
+}
+
+/// A `200` response with a body of a media type other than JSON, like `text/plain`
+pub fn content_response(media_type_name: &str, schema: Schema) -> (StatusCode, Response) {
+    let (status, mut response) = response(200, None, None);
+    response.content.extend(std::iter::once(media_type(
+        media_type_name,
+        ReferenceOr::Item(schema),
+    )));
+    (status, response)
+}
+
+/// Adds a response to `responses`.
+///
+/// If there already is a response with the same status code, the descriptions are joined and bodies of the same media type are combined using `oneOf`.
+pub fn insert_response(responses: &mut Responses, (status, response): (StatusCode, Response)) {
+    match responses.responses.get_mut(&status) {
+        Some(ReferenceOr::Item(existing)) => merge_response(existing, response),
+        Some(ReferenceOr::Reference { .. }) => {}
+        None => {
+            responses
+                .responses
+                .insert(status, ReferenceOr::Item(response));
+        }
+    }
+}
+
+/// Adds a response to the `default` response of `responses`, like [`insert_response`]
+pub fn insert_default_response(responses: &mut Responses, response: Response) {
+    match &mut responses.default {
+        Some(ReferenceOr::Item(existing)) => merge_response(existing, response),
+        Some(ReferenceOr::Reference { .. }) => {}
+        None => responses.default = Some(ReferenceOr::Item(response)),
+    }
+}
+
+fn merge_response(existing: &mut Response, response: Response) {
+    if !existing
+        .description
+        .split("\n\n")
+        .any(|description| description == response.description)
+    {
+        existing.description = format!("{}\n\n{}", existing.description, response.description);
+    }
+    existing.headers.extend(response.headers);
+    for (media_type, content) in response.content {
+        let existing = match existing.content.get_mut(&media_type) {
+            Some(existing) => existing,
+            None => {
+                existing.content.insert(media_type, content);
+                continue;
+            }
+        };
+        existing.schema = match (existing.schema.take(), content.schema) {
+            (Some(a), Some(b)) if a != b => Some(ReferenceOr::Item(match a {
+                ReferenceOr::Item(Schema {
+                    schema_data,
+                    schema_kind: SchemaKind::OneOf { mut one_of },
+                }) if schema_data == SchemaData::default() => {
+                    if !one_of.contains(&b) {
+                        one_of.push(b);
+                    }
+                    one_of_schema(one_of)
+                }
+                a => one_of_schema(vec![a, b]),
+            })),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+/// Adds a security scheme to `components/securitySchemes` and requires it in every security requirement of `op`.
+///
+/// If the scheme is not `required`, every requirement is also kept without it.
+pub fn add_security_requirement(
+    openapi: &mut OpenAPI,
+    op: &mut Operation,
+    (name, scheme): (String, SecurityScheme),
+    scopes: Vec<String>,
+    required: bool,
+) {
+    openapi
+        .components
+        .get_or_insert_with(Default::default)
+        .security_schemes
+        .entry(name.clone())
+        .or_insert(ReferenceOr::Item(scheme));
+
+    let requirements = op
+        .security
+        .take()
+        .unwrap_or_else(|| vec![SecurityRequirement::default()]);
+    let mut security = Vec::with_capacity(requirements.len());
+    for requirement in requirements {
+        let mut with_scheme = requirement.clone();
+        with_scheme.insert(name.clone(), scopes.clone());
+        security.push(with_scheme);
+        if !required {
+            security.push(requirement);
+        }
+    }
+    op.security = Some(security);
+}
+
+/// Serializes the `api`, leaving out the `bearerFormat: null` which `openapiv3` writes for `http` security schemes without a bearer format
+pub fn openapi_value(api: &OpenAPI) -> Result<serde_yaml::Value, serde_yaml::Error> {
+    let mut value = serde_yaml::to_value(api)?;
+    let schemes = value
+        .get_mut("components")
+        .and_then(|components| components.get_mut("securitySchemes"))
+        .and_then(serde_yaml::Value::as_mapping_mut);
+    for (_, scheme) in schemes.into_iter().flat_map(|schemes| schemes.iter_mut()) {
+        if let Some(scheme) = scheme.as_mapping_mut() {
+            let bearer_format = serde_yaml::Value::from("bearerFormat");
+            if scheme.get(&bearer_format) == Some(&serde_yaml::Value::Null) {
+                scheme.remove(&bearer_format);
+            }
         }
+    }
+    Ok(value)
+}
+
+fn text_response<E: std::fmt::Display>(
+    body: Result<String, E>,
+    content_type: &'static str,
+) -> hyper::Response<hyper::Body> {
+    let (status, content_type, body) = match body {
+        Ok(body) => (hyper::StatusCode::OK, content_type, body),
+        Err(err) => (
+            hyper::StatusCode::INTERNAL_SERVER_ERROR,
+            "text/plain",
+            err.to_string(),
+        ),
     };
+    hyper::Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, content_type)
+        .body(hyper::Body::from(body))
+        .unwrap()
+}
+
+pub fn yaml_response(api: &OpenAPI) -> hyper::Response<hyper::Body> {
+    text_response(crate::to_yaml(api), "text/x-yaml")
+}
+
+pub fn json_response(api: &OpenAPI) -> hyper::Response<hyper::Body> {
+    text_response(crate::to_json(api), "application/json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openapi_traits::{DescribeSchema, SchemaView};
+    use crate::schema_generator::SchemaGenerator;
+
+    fn string() -> ReferenceOr<Schema> {
+        ReferenceOr::Item(ty_schema(Type::String(Default::default())))
+    }
+
+    #[test]
+    fn flatten_merges_inline_objects() {
+        let object = object_schema(vec![("a".into(), string())], vec!["a".into()]);
+        let inner = object_schema(vec![("b".into(), string())], vec!["b".into()]);
+        let optional = object_schema(vec![("c".into(), string())], vec!["c".into()]);
+
+        let schema = flatten_schema(
+            object,
+            vec![
+                (ReferenceOr::Item(inner), false),
+                (ReferenceOr::Item(optional), true),
+            ],
+        );
+        let object = match schema.schema_kind {
+            SchemaKind::Type(Type::Object(object)) => object,
+            _ => panic!("expected an object"),
+        };
+        assert_eq!(
+            object.properties.keys().collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(object.required, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn aliases_are_optional_copies_of_properties() {
+        let object = object_schema(vec![("a".into(), string())], vec!["a".into()]);
+        let aliased = alias_properties(object, &[("a", "b"), ("missing", "c")]);
+        assert_eq!(
+            aliased,
+            object_schema(vec![("a".into(), string()), ("b".into(), string())], vec![])
+        );
+    }
+
+    #[test]
+    fn formats_of_integers_and_numbers() {
+        let integer = ReferenceOr::Item(ty_schema(Type::Integer(Default::default())));
+        let number = ReferenceOr::Item(ty_schema(Type::Number(Default::default())));
+        assert_eq!(
+            constrain_format(integer, "int32"),
+            ReferenceOr::Item(ty_schema(Type::Integer(IntegerType {
+                format: VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32),
+                ..Default::default()
+            })))
+        );
+        assert_eq!(
+            constrain_format(number, "double"),
+            ReferenceOr::Item(ty_schema(Type::Number(NumberType {
+                format: VariantOrUnknownOrEmpty::Item(NumberFormat::Double),
+                ..Default::default()
+            })))
+        );
+    }
+
+    #[test]
+    fn type_names() {
+        assert_eq!(type_name::<i32>(), "i32");
+        assert_eq!(type_name::<Vec<String>>(), "Vec_String");
+        assert_eq!(type_name::<&str>(), "str");
+        assert_eq!(type_name::<[Option<u8>; 3]>(), "Option_u8_3");
+        assert_eq!(type_name::<(i32, Schema)>(), "i32_Schema");
+    }
+
+    #[test]
+    fn flatten_composes_references() {
+        let object = object_schema(vec![("a".into(), string())], vec![]);
+        let reference = ReferenceOr::ref_("#/components/schemas/Inner");
+
+        let schema = flatten_schema(object.clone(), vec![(reference.clone(), false)]);
+        assert_eq!(
+            schema.schema_kind,
+            SchemaKind::AllOf {
+                all_of: vec![reference, ReferenceOr::Item(object)]
+            }
+        );
+    }
+
+    struct Extra;
+    impl DescribeSchema for Extra {
+        fn describe_schema(_: &mut SchemaGenerator) -> Schema {
+            object_schema(vec![("b".into(), string())], vec!["b".into()])
+        }
+        fn ref_name() -> Option<String> {
+            Some("Extra".to_string())
+        }
+    }
+
+    #[test]
+    fn flattened_optional_references_may_be_missing() {
+        let mut gen = SchemaGenerator::new(SchemaView::Response);
+        let object = object_schema(vec![("a".into(), string())], vec![]);
+
+        let schema = flatten_schema(
+            object.clone(),
+            vec![
+                (gen.subschema_for::<Option<Extra>>(), true),
+                (gen.subschema_for::<Option<Extra>>(), true),
+            ],
+        );
+        let any = ReferenceOr::Item(kind_schema(SchemaKind::Any(Default::default())));
+        let optional_extra = ReferenceOr::Item(kind_schema(SchemaKind::AnyOf {
+            any_of: vec![ReferenceOr::ref_("#/components/schemas/Extra"), any],
+        }));
+        assert_eq!(
+            schema.schema_kind,
+            SchemaKind::AllOf {
+                all_of: vec![optional_extra, ReferenceOr::Item(object)]
+            }
+        );
+    }
+
+    #[test]
+    fn responses_with_the_same_status_are_merged() {
+        let reference = ReferenceOr::ref_("#/components/schemas/Error");
+        let mut responses = Responses::default();
+        insert_response(&mut responses, response(400, None, Some(reference.clone())));
+        insert_response(
+            &mut responses,
+            response(400, Some("Invalid"), Some(string())),
+        );
+        insert_response(&mut responses, response(400, None, Some(string())));
+
+        let response = match &responses.responses[&StatusCode::Code(400)] {
+            ReferenceOr::Item(response) => response,
+            _ => panic!("expected a response"),
+        };
+        assert_eq!(response.description, "Bad Request\n\nInvalid");
+        assert_eq!(
+            response.content["application/json"].schema,
+            Some(ReferenceOr::Item(one_of_schema(vec![reference, string()])))
+        );
+    }
+
+    #[test]
+    fn optional_security_schemes_are_alternatives() {
+        let http = |scheme: &str| SecurityScheme::HTTP {
+            scheme: scheme.to_string(),
+            bearer_format: None,
+        };
+        let mut openapi = OpenAPI::default();
+        let mut op = Operation::default();
+        add_security_requirement(
+            &mut openapi,
+            &mut op,
+            ("basic".into(), http("basic")),
+            vec![],
+            false,
+        );
+        add_security_requirement(
+            &mut openapi,
+            &mut op,
+            ("bearer".into(), http("bearer")),
+            vec![],
+            true,
+        );
 
-    let mut res = hyper::Response::new(hyper::Body::from(bytes));
-    res.headers_mut().insert(
-        hyper::header::CONTENT_TYPE,
-        hyper::header::HeaderValue::from_static("text/x-yaml"),
-    );
-    res
+        let names: Vec<Vec<&String>> = op
+            .security
+            .iter()
+            .flatten()
+            .map(|requirement| requirement.keys().collect())
+            .collect();
+        assert_eq!(names, vec![vec!["basic", "bearer"], vec!["bearer"]]);
+        assert_eq!(openapi.components.unwrap().security_schemes.len(), 2);
+    }
+
+    #[test]
+    fn missing_bearer_formats_are_not_serialized() {
+        let mut openapi = OpenAPI::default();
+        let mut op = Operation::default();
+        let schemes = vec![
+            ("basicAuth", "basic", None),
+            ("jwtAuth", "bearer", Some("JWT")),
+        ];
+        for (name, scheme, bearer_format) in schemes {
+            let scheme = SecurityScheme::HTTP {
+                scheme: scheme.to_string(),
+                bearer_format: bearer_format.map(str::to_string),
+            };
+            add_security_requirement(&mut openapi, &mut op, (name.into(), scheme), vec![], true);
+        }
+
+        let value = openapi_value(&openapi).unwrap();
+        let schemes = &value["components"]["securitySchemes"];
+        assert_eq!(
+            schemes["basicAuth"],
+            serde_yaml::from_str::<serde_yaml::Value>("type: http\nscheme: basic").unwrap()
+        );
+        assert_eq!(
+            schemes["jwtAuth"],
+            serde_yaml::from_str::<serde_yaml::Value>(
+                "type: http\nscheme: bearer\nbearerFormat: JWT"
+            )
+            .unwrap()
+        );
+    }
 }